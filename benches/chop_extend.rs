@@ -0,0 +1,96 @@
+//! 🏎️ Benchmark building the loom level by level with the hash-based `chop` and `extend_threads` against the previous implementations which searched the pins and every cut for every thread end.
+//!
+//! ```text
+//! cargo bench --bench chop_extend
+//! ```
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use rayon::prelude::*;
+use weaver::graph::ops::prelude::*;
+
+/// The previous implementations, kept as the baseline.
+mod quadratic {
+    use super::*;
+
+    /// Previous `SegmentYarn::chop`: `pins.contains()` on a Vec for every vertex of the yarn.
+    pub fn chop(mut yarn: Warp, pins: &PinCushion) -> Warps {
+        match !pins.is_empty() {
+            true => yarn
+                .par_iter()
+                .enumerate()
+                .filter_map(|(i, p)| pins.contains(p).then_some(i))
+                .collect::<Vec<_>>()
+                .into_iter()
+                .enumerate()
+                .rev()
+                .flat_map(|(idx, i)| match idx == 0 {
+                    true if !pins.contains(&yarn[0]) => {
+                        vec![
+                            yarn.drain(i + 1..).collect::<Warp>(),
+                            yarn.drain(..).rev().collect::<Warp>(),
+                        ]
+                    }
+                    _ => vec![yarn.drain(i..).collect::<Warp>()],
+                })
+                .collect(),
+            false => {
+                yarn.reverse();
+                vec![yarn]
+            }
+        }
+    }
+
+    /// Previous `ExtendLoomThreads::extend_threads`: every cut is compared with both ends of every thread.
+    pub fn extend_threads(loom: &mut Loom, mut warps: Warps) {
+        loom.iter_mut().for_each(|thread: &mut LoomThread| {
+            for warp in warps.iter_mut().filter(|w| !w.is_empty()) {
+                match (thread[0] == warp[0], thread[thread.len() - 1] == warp[0]) {
+                    (true, _) => {
+                        thread.pop_front();
+                        warp.drain(..).for_each(|item| thread.push_front(item));
+                    }
+                    (_, true) => {
+                        thread.pop_back();
+                        thread.extend(warp.drain(..));
+                    }
+                    _ => continue,
+                }
+            }
+        });
+        warps.iter_mut().filter(|s| !s.is_empty()).for_each(|seq| {
+            loom.push(seq.drain(..).collect::<LoomThread>());
+        });
+    }
+}
+
+/// Build the loom for n with either the current or the previous chop and extend.
+fn build_loom(n: usize, yarns: &Yarns, previous: bool) -> Loom {
+    let mut loom = Loom::with_capacity(n.loom_size());
+    n.z_color_len().into_iter().for_each(|((z, color), len)| {
+        let mut pins = loom.pin_thread_ends(z);
+        let yarn = yarns.prep(z, color, len);
+        match previous {
+            true => quadratic::extend_threads(&mut loom, quadratic::chop(yarn, &pins)),
+            false => loom.extend_threads(yarn.chop(&mut pins)),
+        }
+    });
+    loom
+}
+
+fn bench_chop_extend(c: &mut Criterion) {
+    let mut group = c.benchmark_group("chop_extend");
+    group.sample_size(10);
+    for n in [100, 200, 400] {
+        let yarns = Yarns::color_spun(Spindle::spin_out(n));
+        assert_eq!(build_loom(n, &yarns, false), build_loom(n, &yarns, true));
+        group.bench_with_input(BenchmarkId::new("hashed", n), &n, |b, &n| {
+            b.iter(|| build_loom(n, &yarns, false))
+        });
+        group.bench_with_input(BenchmarkId::new("quadratic", n), &n, |b, &n| {
+            b.iter(|| build_loom(n, &yarns, true))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_chop_extend);
+criterion_main!(benches);
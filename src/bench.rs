@@ -0,0 +1,262 @@
+/// ⏱️ Time the weave of each n over several runs, summarize the runs, write them out and compare them with a baseline written before.
+use std::{
+    fmt::Write as _,
+    fs::File,
+    io::{BufReader, Write},
+    time::Instant,
+};
+
+use serde::{Deserialize, Serialize};
+use weaver::{certify_stream, weave_with, Certify, InfoN, MergeStrategy, SequenceID, WeaveError};
+
+use crate::cli::BenchFormat;
+
+/// Above a hundred million vertices certify streaming with one bit per vertex instead of holding the sets of the full certification.
+const STREAM_CERTIFY_ORDER: usize = 100_000_000;
+
+/// 📊 The runs of one n: weave times in seconds, the time to certify the last weave and the peak resident set size in kB.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct BenchRecord {
+    pub n: usize,
+    pub order: usize,
+    pub merge: String,
+    pub repeats: usize,
+    pub min: f64,
+    pub median: f64,
+    pub mean: f64,
+    pub stddev: f64,
+    pub p95: f64,
+    pub certify: f64,
+    /// Not known off Linux.
+    pub peak_rss_kb: Option<u64>,
+}
+
+impl BenchRecord {
+    /// Summarize the weave times of the runs.
+    pub fn new(
+        n: usize,
+        merge: MergeStrategy,
+        mut times: Vec<f64>,
+        certify: f64,
+        peak_rss_kb: Option<u64>,
+    ) -> BenchRecord {
+        times.sort_by(f64::total_cmp);
+        let repeats = times.len();
+        let mean = times.iter().sum::<f64>() / repeats as f64;
+        let variance = times.iter().map(|time| (time - mean).powi(2)).sum::<f64>() / repeats as f64;
+        let median = match repeats % 2 {
+            0 => (times[repeats / 2 - 1] + times[repeats / 2]) / 2.0,
+            _ => times[repeats / 2],
+        };
+        BenchRecord {
+            n,
+            order: n.get_order_from_n(),
+            merge: merge.to_string(),
+            repeats,
+            min: times[0],
+            median,
+            mean,
+            stddev: variance.sqrt(),
+            // nearest rank.
+            p95: times[(repeats * 95).div_ceil(100) - 1],
+            certify,
+            peak_rss_kb,
+        }
+    }
+
+    /// A row like those of the running times in the README.
+    pub fn row(&self) -> String {
+        format!(
+            "| 🇳 {:>4} | ⭕️ {:>10} | 🕗 {:.10} ",
+            self.n, self.order, self.min
+        )
+    }
+}
+
+/// Weave n `repeats` times, certify the last weave and record the peak memory of the runs.
+pub fn bench_n(n: usize, repeats: usize, merge: MergeStrategy) -> Result<BenchRecord, WeaveError> {
+    reset_peak_rss();
+    let (mut times, mut solution) = (Vec::with_capacity(repeats), None);
+    for _ in 0..repeats {
+        // Drop the last weave before the next so that two are never held at once.
+        drop(solution.take());
+        let start = Instant::now();
+        let woven = weave_with(n, merge)?;
+        times.push(start.elapsed().as_secs_f64());
+        solution = Some(woven);
+    }
+    let solution = solution.expect("at least one repeat");
+    let (order, max_absumv) = (n.get_order_from_n(), n.get_max_absumv());
+    let start = Instant::now();
+    let report = match order > STREAM_CERTIFY_ORDER {
+        true => certify_stream(solution.iter().copied(), order, max_absumv),
+        false => solution.certify_report(order, max_absumv),
+    };
+    let certify = start.elapsed().as_secs_f64();
+    if report.id != SequenceID::HamCycle {
+        return Err(WeaveError::NotAHamCycle(Box::new(report)));
+    }
+    Ok(BenchRecord::new(n, merge, times, certify, peak_rss_kb()))
+}
+
+/// Peak resident set size of the process in kB, from `VmHWM` in `/proc/self/status`.
+fn peak_rss_kb() -> Option<u64> {
+    std::fs::read_to_string("/proc/self/status")
+        .ok()?
+        .lines()
+        .find_map(|line| line.strip_prefix("VmHWM:"))?
+        .trim()
+        .trim_end_matches("kB")
+        .trim()
+        .parse()
+        .ok()
+}
+
+/// Reset the peak resident set size to the current one so that each n reports its own peak. Does nothing where that isn't supported.
+fn reset_peak_rss() {
+    let _ = std::fs::write("/proc/self/clear_refs", "5");
+}
+
+/// Render the records as json, csv or the running times block of the README.
+pub fn render(records: &[BenchRecord], format: BenchFormat) -> Result<String, WeaveError> {
+    match format {
+        BenchFormat::Table => Ok(records.iter().map(|record| record.row() + "\n").collect()),
+        BenchFormat::Markdown => {
+            let mut block = String::from("```\n");
+            records
+                .iter()
+                .for_each(|record| writeln!(block, "{}", record.row()).unwrap());
+            block.push_str("```\n");
+            Ok(block)
+        }
+        BenchFormat::Json => Ok(serde_json::to_string_pretty(records)? + "\n"),
+        BenchFormat::Csv => {
+            let mut writer = csv::Writer::from_writer(Vec::new());
+            records
+                .iter()
+                .try_for_each(|record| writer.serialize(record))?;
+            let bytes = writer
+                .into_inner()
+                .map_err(|error| WeaveError::Io(error.to_string()))?;
+            Ok(String::from_utf8(bytes).expect("csv of numbers is utf-8"))
+        }
+    }
+}
+
+/// Write the rendered records to the file at `file_path`.
+pub fn write_records(
+    records: &[BenchRecord],
+    format: BenchFormat,
+    file_path: &str,
+) -> Result<(), WeaveError> {
+    File::create(file_path)?.write_all(render(records, format)?.as_bytes())?;
+    Ok(())
+}
+
+/// Read the records of a baseline from a json or csv file written by `bench`.
+pub fn read_baseline(file_path: &str) -> Result<Vec<BenchRecord>, WeaveError> {
+    match file_path.ends_with(".csv") {
+        true => Ok(csv::Reader::from_path(file_path)?
+            .into_deserialize()
+            .collect::<Result<_, _>>()?),
+        false => Ok(serde_json::from_reader(BufReader::new(File::open(
+            file_path,
+        )?))?),
+    }
+}
+
+/// 🐢 An n whose median weave time is slower than its baseline by more than the threshold.
+#[derive(Debug, PartialEq)]
+pub struct Regression {
+    pub n: usize,
+    pub baseline: f64,
+    pub median: f64,
+    /// How much slower, in percent of the baseline.
+    pub slower: f64,
+}
+
+/// Compare the median weave time of each n with the baseline of the same n and merge strategy, flagging those slower by more than `threshold` percent.
+pub fn regressions(
+    records: &[BenchRecord],
+    baseline: &[BenchRecord],
+    threshold: f64,
+) -> Vec<Regression> {
+    records
+        .iter()
+        .filter_map(|record| {
+            let base = baseline
+                .iter()
+                .find(|base| base.n == record.n && base.merge == record.merge)?;
+            let slower = (record.median / base.median - 1.0) * 100.0;
+            (slower > threshold).then_some(Regression {
+                n: record.n,
+                baseline: base.median,
+                median: record.median,
+                slower,
+            })
+        })
+        .collect()
+}
+
+// 🩺 TEST
+//
+// 🩺 Test the statistics of the runs and the comparison with a baseline.
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    /// Min, median, mean, stddev and p95 of the runs in any order.
+    fn test_bench_record() {
+        let times = vec![4.0, 2.0, 5.0, 4.0, 5.0, 7.0, 4.0, 9.0];
+        let record = BenchRecord::new(3, MergeStrategy::Sequential, times, 0.5, None);
+        assert_eq!(record.order, 80);
+        assert_eq!(record.repeats, 8);
+        assert_eq!(record.min, 2.0);
+        assert_eq!(record.median, 4.5);
+        assert_eq!(record.mean, 5.0);
+        assert_eq!(record.stddev, 2.0);
+        assert_eq!(record.p95, 9.0);
+        let record = BenchRecord::new(3, MergeStrategy::Sequential, vec![1.0], 0.5, Some(10));
+        assert_eq!(
+            [record.min, record.median, record.p95, record.stddev],
+            [1.0, 1.0, 1.0, 0.0]
+        );
+    }
+
+    #[test]
+    /// Records written as json and csv read back as a baseline, and only n slower than the threshold are flagged.
+    fn test_regressions() {
+        let records = (1..=3)
+            .map(|n| bench_n(n, 3, MergeStrategy::Sequential).unwrap())
+            .collect::<Vec<_>>();
+        for file_path in ["test_regressions.json", "test_regressions.csv"] {
+            let format = match file_path.ends_with(".csv") {
+                true => BenchFormat::Csv,
+                false => BenchFormat::Json,
+            };
+            write_records(&records, format, file_path).unwrap();
+            let read = read_baseline(file_path).unwrap();
+            assert_eq!(read.len(), records.len());
+            read.iter().zip(&records).for_each(|(read, record)| {
+                assert_eq!(
+                    [read.n, read.order, read.repeats],
+                    [record.n, record.order, 3]
+                );
+                assert!((read.median - record.median).abs() <= f64::EPSILON * record.median);
+            });
+            std::fs::remove_file(file_path).unwrap();
+        }
+        let mut baseline = records.clone();
+        baseline[1].median = records[1].median / 2.0;
+        baseline[2].median = records[2].median / 1.05;
+        let found = regressions(&records, &baseline, 10.0);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].n, 2);
+        assert!((found[0].slower - 100.0).abs() < 1e-9);
+        assert!(regressions(&records, &baseline[..1], 10.0).is_empty());
+        assert!(render(&records, BenchFormat::Markdown)
+            .unwrap()
+            .contains("| 🇳    2 | ⭕️         32 | 🕗 "));
+    }
+}
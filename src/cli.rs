@@ -0,0 +1,540 @@
+/// 🎛️ Parse the command line into a `Command`: a subcommand, its positional arguments and its `--flag value` pairs.
+/// Anything that isn't understood is a `UsageError` instead of a silent fallback.
+use std::{collections::HashMap, fmt, str::FromStr};
+
+use weaver::{CheckedInfoN, MergeStrategy, MAX_N};
+
+pub const USAGE: &str = "\
+usage: weaver <command> [flags]
+
+commands:
+  solve   --n N | --range START..END [--step S] [--repeats R] [--merge sequential|tree]
+          weave, certify and time each n
+  bench   --n N | --range START..END [--step S] [--repeats R] [--merge sequential|tree]
+          [--format table|json|csv|markdown] [--out PATH] [--baseline PATH] [--threshold PERCENT]
+          time each n over several runs: min, median, mean, stddev and p95 of the weave, certify time and peak memory,
+          exits with 1 if the median of any n is slower than in the json or csv baseline by more than the threshold, 10 by default
+  verify  <file> [--n N]
+          certify the tour in a .csv, .json, .wvt or raw binary file, n inferred from its length
+  export  --n N [--format csv|json|wvt|bin] [--out PATH] [--merge sequential|tree]
+          weave n and write it to a file, solution_N.<format> by default
+  encode  --n N [--out PATH]
+          weave n and encode it as a string
+  decode  <file> [--n N] [--format csv|json|wvt|bin] [--out PATH]
+          decode the string in file and certify it, writing the tour if --out is given
+  grade   --n N
+          count the edges along each axis and the nonturns of the weave of n
+  info    --n N | --order ORDER
+          the sizes of discocube n
+  profile --n N [--merge sequential|tree] [--format table|json] [--out PATH]
+          time each phase of the weave and each level of the loom, with memory when built with --features profile
+
+ranges are inclusive: --range 1..100 runs n = 1 to 100.";
+
+/// The command line could not be understood.
+#[derive(Debug, PartialEq, Eq)]
+pub struct UsageError(pub String);
+
+impl fmt::Display for UsageError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// File formats a tour is written in.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Format {
+    #[default]
+    Csv,
+    Json,
+    Wvt,
+    Bin,
+}
+
+impl Format {
+    /// Extension of files in the format.
+    pub fn extension(self) -> &'static str {
+        match self {
+            Format::Csv => "csv",
+            Format::Json => "json",
+            Format::Wvt => "wvt",
+            Format::Bin => "bin",
+        }
+    }
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(format: &str) -> Result<Format, String> {
+        match format {
+            "csv" => Ok(Format::Csv),
+            "json" => Ok(Format::Json),
+            "wvt" => Ok(Format::Wvt),
+            "bin" => Ok(Format::Bin),
+            _ => Err(format!(
+                "unknown format: {format}. Use csv, json, wvt or bin"
+            )),
+        }
+    }
+}
+
+/// What `bench` writes: the rows of the README as it runs, the records as json or csv, or the running times block of the README.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum BenchFormat {
+    #[default]
+    Table,
+    Json,
+    Csv,
+    Markdown,
+}
+
+impl FromStr for BenchFormat {
+    type Err = String;
+
+    fn from_str(format: &str) -> Result<BenchFormat, String> {
+        match format {
+            "table" => Ok(BenchFormat::Table),
+            "json" => Ok(BenchFormat::Json),
+            "csv" => Ok(BenchFormat::Csv),
+            "markdown" => Ok(BenchFormat::Markdown),
+            _ => Err(format!(
+                "unknown format: {format}. Use table, json, csv or markdown"
+            )),
+        }
+    }
+}
+
+/// What `profile` writes: a table or json.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ProfileFormat {
+    #[default]
+    Table,
+    Json,
+}
+
+impl FromStr for ProfileFormat {
+    type Err = String;
+
+    fn from_str(format: &str) -> Result<ProfileFormat, String> {
+        match format {
+            "table" => Ok(ProfileFormat::Table),
+            "json" => Ok(ProfileFormat::Json),
+            _ => Err(format!("unknown format: {format}. Use table or json")),
+        }
+    }
+}
+
+/// Which n to run and how.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Runs {
+    pub ns: Vec<usize>,
+    pub repeats: usize,
+    pub merge: MergeStrategy,
+}
+
+/// What to do.
+#[derive(Debug, PartialEq)]
+pub enum Command {
+    Solve(Runs),
+    Bench {
+        runs: Runs,
+        format: BenchFormat,
+        out: Option<String>,
+        baseline: Option<String>,
+        threshold: f64,
+    },
+    Verify {
+        file: String,
+        n: Option<usize>,
+    },
+    Export {
+        n: usize,
+        format: Format,
+        out: String,
+        merge: MergeStrategy,
+    },
+    Encode {
+        n: usize,
+        out: Option<String>,
+    },
+    Decode {
+        file: String,
+        n: Option<usize>,
+        format: Format,
+        out: Option<String>,
+    },
+    Grade {
+        n: usize,
+    },
+    Info {
+        n: usize,
+    },
+    Profile {
+        n: usize,
+        merge: MergeStrategy,
+        format: ProfileFormat,
+        out: Option<String>,
+    },
+    Help,
+}
+
+/// The positional arguments and flags given to a command, each flag checked against the ones the command takes.
+struct Args<'a> {
+    command: &'a str,
+    positional: Vec<&'a str>,
+    flags: HashMap<&'a str, &'a str>,
+}
+
+impl<'a> Args<'a> {
+    fn new(command: &'a str, args: &'a [String], known: &[&str]) -> Result<Args<'a>, UsageError> {
+        let (mut positional, mut flags) = (Vec::new(), HashMap::new());
+        let mut args = args.iter().map(String::as_str);
+        while let Some(arg) = args.next() {
+            match arg.strip_prefix("--") {
+                Some(flag) if known.contains(&flag) => {
+                    let value = args
+                        .next()
+                        .ok_or(UsageError(format!("--{flag} of {command} needs a value")))?;
+                    if flags.insert(flag, value).is_some() {
+                        return Err(UsageError(format!("--{flag} is given twice")));
+                    }
+                }
+                Some(flag) => return Err(UsageError(format!("{command} takes no flag --{flag}"))),
+                None => positional.push(arg),
+            }
+        }
+        Ok(Args {
+            command,
+            positional,
+            flags,
+        })
+    }
+
+    /// The value of the flag parsed, if it was given.
+    fn get<T: FromStr>(&self, flag: &str) -> Result<Option<T>, UsageError>
+    where
+        T::Err: fmt::Display,
+    {
+        self.flags
+            .get(flag)
+            .map(|value| {
+                value
+                    .parse()
+                    .map_err(|error| UsageError(format!("--{flag} {value}: {error}")))
+            })
+            .transpose()
+    }
+
+    /// The value of a flag which has to be given.
+    fn require<T: FromStr>(&self, flag: &str) -> Result<T, UsageError>
+    where
+        T::Err: fmt::Display,
+    {
+        self.get(flag)?
+            .ok_or(UsageError(format!("{} needs --{flag}", self.command)))
+    }
+
+    /// n given with `--n`, checked to have a discocube which can be woven.
+    fn n(&self) -> Result<Option<usize>, UsageError> {
+        self.get("n")?.map(check_n).transpose()
+    }
+
+    /// The only positional argument: a file.
+    fn file(&self) -> Result<String, UsageError> {
+        match self.positional[..] {
+            [file] => Ok(file.to_string()),
+            [] => Err(UsageError(format!("{} needs a file", self.command))),
+            _ => Err(UsageError(format!("{} takes one file", self.command))),
+        }
+    }
+
+    /// Commands other than those reading a file take no positional argument.
+    fn no_positional(&self) -> Result<(), UsageError> {
+        match self.positional.first() {
+            Some(arg) => Err(UsageError(format!(
+                "{} takes no argument {arg}: use flags",
+                self.command
+            ))),
+            None => Ok(()),
+        }
+    }
+
+    /// The n given by `--n` or by `--range` and `--step`, the number of repeats and the merge strategy.
+    fn runs(&self, repeats: usize) -> Result<Runs, UsageError> {
+        self.no_positional()?;
+        let step = self.get("step")?.unwrap_or(1);
+        if step == 0 {
+            return Err(UsageError("--step must be at least 1".to_string()));
+        }
+        let ns = match (self.n()?, self.flags.get("range")) {
+            (Some(n), None) => vec![n],
+            (None, Some(range)) => {
+                let (start, end) = parse_range(range)?;
+                (start..=end).step_by(step).collect()
+            }
+            (Some(_), Some(_)) => {
+                return Err(UsageError(
+                    "give either --n or --range, not both".to_string(),
+                ))
+            }
+            (None, None) => {
+                return Err(UsageError(format!("{} needs --n or --range", self.command)))
+            }
+        };
+        let repeats = self.get("repeats")?.unwrap_or(repeats);
+        if repeats == 0 {
+            return Err(UsageError("--repeats must be at least 1".to_string()));
+        }
+        Ok(Runs {
+            ns,
+            repeats,
+            merge: self.get("merge")?.unwrap_or_default(),
+        })
+    }
+}
+
+/// n has a discocube and can be woven.
+fn check_n(n: usize) -> Result<usize, UsageError> {
+    match n {
+        0 => Err(UsageError("n must be at least 1".to_string())),
+        n if n > MAX_N => Err(UsageError(format!("n {n} is larger than max n {MAX_N}"))),
+        n => Ok(n),
+    }
+}
+
+/// `START..END` or `START..=END`, both inclusive.
+fn parse_range(range: &str) -> Result<(usize, usize), UsageError> {
+    let invalid = || UsageError(format!("--range {range}: expected START..END"));
+    let (start, end) = range.split_once("..").ok_or_else(invalid)?;
+    let end = end.strip_prefix('=').unwrap_or(end);
+    let (start, end) = (
+        check_n(start.parse().map_err(|_| invalid())?)?,
+        check_n(end.parse().map_err(|_| invalid())?)?,
+    );
+    match start <= end {
+        true => Ok((start, end)),
+        false => Err(UsageError(format!("--range {range}: start is after end"))),
+    }
+}
+
+impl Command {
+    /// Parse the arguments following the name of the binary.
+    pub fn parse(args: &[String]) -> Result<Command, UsageError> {
+        let Some((command, rest)) = args.split_first() else {
+            return Err(UsageError("no command given".to_string()));
+        };
+        let args = |known| Args::new(command, rest, known);
+        let run_flags = &["n", "range", "step", "repeats", "merge"];
+        match command.as_str() {
+            "solve" => Ok(Command::Solve(args(run_flags)?.runs(1)?)),
+            "bench" => {
+                let args = args(&[
+                    "n",
+                    "range",
+                    "step",
+                    "repeats",
+                    "merge",
+                    "format",
+                    "out",
+                    "baseline",
+                    "threshold",
+                ])?;
+                let threshold = args.get("threshold")?.unwrap_or(10.0);
+                if !(threshold >= 0.0 && f64::is_finite(threshold)) {
+                    return Err(UsageError(format!(
+                        "--threshold {threshold}: expected a percentage of at least 0"
+                    )));
+                }
+                Ok(Command::Bench {
+                    runs: args.runs(5)?,
+                    format: args.get("format")?.unwrap_or_default(),
+                    out: args.get("out")?,
+                    baseline: args.get("baseline")?,
+                    threshold,
+                })
+            }
+            "verify" => {
+                let args = args(&["n"])?;
+                Ok(Command::Verify {
+                    file: args.file()?,
+                    n: args.n()?,
+                })
+            }
+            "export" => {
+                let args = args(&["n", "format", "out", "merge"])?;
+                args.no_positional()?;
+                let n = check_n(args.require("n")?)?;
+                let format: Format = args.get("format")?.unwrap_or_default();
+                Ok(Command::Export {
+                    n,
+                    format,
+                    out: args
+                        .get("out")?
+                        .unwrap_or(format!("solution_{n}.{}", format.extension())),
+                    merge: args.get("merge")?.unwrap_or_default(),
+                })
+            }
+            "encode" => {
+                let args = args(&["n", "out"])?;
+                args.no_positional()?;
+                Ok(Command::Encode {
+                    n: check_n(args.require("n")?)?,
+                    out: args.get("out")?,
+                })
+            }
+            "decode" => {
+                let args = args(&["n", "format", "out"])?;
+                Ok(Command::Decode {
+                    file: args.file()?,
+                    n: args.n()?,
+                    format: args.get("format")?.unwrap_or_default(),
+                    out: args.get("out")?,
+                })
+            }
+            "grade" => {
+                let args = args(&["n"])?;
+                args.no_positional()?;
+                Ok(Command::Grade {
+                    n: check_n(args.require("n")?)?,
+                })
+            }
+            "info" => {
+                let args = args(&["n", "order"])?;
+                args.no_positional()?;
+                let n = match (args.n()?, args.get::<usize>("order")?) {
+                    (Some(n), None) => n,
+                    (None, Some(order)) => order
+                        .try_n_from_order()
+                        .map_err(|error| UsageError(format!("--order {order}: {error}")))?,
+                    _ => return Err(UsageError("info needs either --n or --order".to_string())),
+                };
+                Ok(Command::Info { n })
+            }
+            "profile" => {
+                let args = args(&["n", "merge", "format", "out"])?;
+                args.no_positional()?;
+                Ok(Command::Profile {
+                    n: check_n(args.require("n")?)?,
+                    merge: args.get("merge")?.unwrap_or_default(),
+                    format: args.get("format")?.unwrap_or_default(),
+                    out: args.get("out")?,
+                })
+            }
+            "help" | "--help" | "-h" => Ok(Command::Help),
+            _ => Err(UsageError(format!("unknown command: {command}"))),
+        }
+    }
+}
+
+// 🩺 TEST
+//
+// 🩺 Test that the command line is parsed into commands or refused.
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(line: &str) -> Result<Command, UsageError> {
+        Command::parse(
+            &line
+                .split_whitespace()
+                .map(String::from)
+                .collect::<Vec<_>>(),
+        )
+    }
+
+    #[test]
+    /// 🩺 Flags are parsed into the command, with their defaults.
+    fn test_parse() {
+        assert_eq!(
+            parse("solve --range 1..=7 --step 2 --merge tree"),
+            Ok(Command::Solve(Runs {
+                ns: vec![1, 3, 5, 7],
+                repeats: 1,
+                merge: MergeStrategy::TreeReduction
+            }))
+        );
+        assert_eq!(
+            parse("bench --n 100"),
+            Ok(Command::Bench {
+                runs: Runs {
+                    ns: vec![100],
+                    repeats: 5,
+                    merge: MergeStrategy::Sequential
+                },
+                format: BenchFormat::Table,
+                out: None,
+                baseline: None,
+                threshold: 10.0
+            })
+        );
+        assert!(matches!(
+            parse("bench --range 1..4 --format json --baseline base.json --threshold 2.5"),
+            Ok(Command::Bench {
+                format: BenchFormat::Json,
+                baseline: Some(_),
+                threshold: 2.5,
+                ..
+            })
+        ));
+        assert_eq!(
+            parse("export --n 3 --format wvt"),
+            Ok(Command::Export {
+                n: 3,
+                format: Format::Wvt,
+                out: "solution_3.wvt".to_string(),
+                merge: MergeStrategy::Sequential
+            })
+        );
+        assert_eq!(
+            parse("verify tour.csv --n 4"),
+            Ok(Command::Verify {
+                file: "tour.csv".to_string(),
+                n: Some(4)
+            })
+        );
+        assert_eq!(parse("info --order 280"), Ok(Command::Info { n: 5 }));
+        assert_eq!(
+            parse("profile --n 100 --format json"),
+            Ok(Command::Profile {
+                n: 100,
+                merge: MergeStrategy::Sequential,
+                format: ProfileFormat::Json,
+                out: None
+            })
+        );
+    }
+
+    #[test]
+    /// 🩺 Typos and bad values are errors rather than running something else.
+    fn test_parse_errors() {
+        for line in [
+            "",
+            "slove --n 3",
+            "solve",
+            "solve 1 100",
+            "solve --n x",
+            "solve --n 0",
+            "solve --n 3 --range 1..4",
+            "solve --range 5..1",
+            "solve --n 3 --repeats 0",
+            "solve --n 3 --nn 4",
+            "solve --n",
+            "solve --n 3 --n 4",
+            "solve --n 3 --merge binary",
+            "export --n 3 --format xml",
+            "export --n 3 --format markdown",
+            "bench --n 3 --format wvt",
+            "bench --n 3 --threshold -1",
+            "bench --n 3 --threshold NaN",
+            "solve --n 3 --baseline base.json",
+            "verify",
+            "verify a.csv b.csv",
+            "info --order 100",
+            "grade",
+            "profile --n 3 --format csv",
+        ] {
+            assert!(parse(line).is_err(), "{line}");
+        }
+    }
+}
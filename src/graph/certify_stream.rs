@@ -0,0 +1,319 @@
+use rayon::prelude::*;
+
+use super::{
+    error::WeaveError,
+    ops::{
+        certify_solution::{Adjacency, CertifyReport, Discocube, IsAdjacent, SequenceID},
+        graph_info_from_n::InfoN,
+        serialize_csv::CsvVector,
+        translate::rank_unbounded,
+    },
+    sink::TourSink,
+    types::*,
+    weave::check_n,
+    wvt::WvtReader,
+};
+
+/// Number of vertices checked in parallel at a time.
+const BATCH: usize = 1 << 20;
+/// Number of duplicates and of out-of-bounds vertices kept in the report, so the memory used stays one bit per vertex.
+const REPORTED: usize = 16;
+
+/// 🧾 Certify a tour as it streams by, without holding it: visits are marked in a bitset indexed by the rank of each vertex, one bit per vertex of the graph.
+/// Adjacency and bounds are checked on the fly, batch by batch, each batch in parallel.
+/// Also a `TourSink` so `weave_into()` can certify while it weaves.
+pub struct StreamCertifier {
+    graph: Discocube,
+    visited: Vec<u64>,
+    batch: Vec<V3d>,
+    first: Option<V3d>,
+    last: Option<V3d>,
+    len: usize,
+    checks: Checks,
+}
+
+/// What the checks found so far, merged across the threads checking a batch.
+#[derive(Default)]
+struct Checks {
+    duplicates: Vec<(V3d, Vec<usize>)>,
+    out_of_bounds: Vec<(usize, V3d)>,
+    not_adjacent: Option<(usize, usize)>,
+    displacement_sum: [i64; 3],
+    /// The rank and position of each vertex of the batch being checked, whose visits are yet to be marked.
+    ranked: Vec<(u64, usize)>,
+}
+
+impl Checks {
+    fn merge(mut self, other: Checks) -> Checks {
+        self.duplicates.extend(other.duplicates);
+        self.duplicates
+            .sort_unstable_by_key(|(_, positions)| positions[0]);
+        self.duplicates.truncate(REPORTED);
+        self.out_of_bounds.extend(other.out_of_bounds);
+        self.out_of_bounds.sort_unstable();
+        self.out_of_bounds.truncate(REPORTED);
+        self.not_adjacent = match (self.not_adjacent, other.not_adjacent) {
+            (Some(lhs), Some(rhs)) => Some(lhs.min(rhs)),
+            (lhs, rhs) => lhs.or(rhs),
+        };
+        (0..3).for_each(|axis| self.displacement_sum[axis] += other.displacement_sum[axis]);
+        self.ranked.extend(other.ranked);
+        self
+    }
+}
+
+impl StreamCertifier {
+    /// Certify a tour of the graph with `order` vertices whose L1-norms are at most `max_absumv3d`, same as `Certify::certify`.
+    pub fn new(order: Count, max_absumv3d: ScalarXyz) -> StreamCertifier {
+        StreamCertifier {
+            graph: Discocube::new(order, max_absumv3d),
+            visited: vec![0; order.div_ceil(64)],
+            batch: Vec::with_capacity(BATCH.min(order)),
+            first: None,
+            last: None,
+            len: 0,
+            checks: Checks::default(),
+        }
+    }
+
+    /// Same as `put`, which never fails.
+    fn batch_put(&mut self, vert: V3d) {
+        self.first.get_or_insert(vert);
+        self.batch.push(vert);
+        if self.batch.len() == BATCH {
+            self.check_batch();
+        }
+    }
+
+    /// Check the vertices put since the last batch. The checks of a batch run in parallel.
+    /// Visits are then marked in order of rank and position, so of the visits to a vertex within a batch the first is never the one reported.
+    fn check_batch(&mut self) {
+        let (base, prev, graph) = (self.len, self.last, self.graph);
+        let batch = &self.batch;
+        let mut checks = batch
+            .par_iter()
+            .enumerate()
+            .fold(Checks::default, |mut checks, (idx, &vert)| {
+                let position = base + idx;
+                let before = match idx {
+                    0 => prev,
+                    _ => Some(batch[idx - 1]),
+                };
+                if before.is_some_and(|before| !before.is_adj_to(vert))
+                    && checks.not_adjacent.is_none()
+                {
+                    checks.not_adjacent = Some((position - 1, position));
+                }
+                vert.iter()
+                    .enumerate()
+                    .for_each(|(axis, &scalar)| checks.displacement_sum[axis] += scalar as i64);
+                // Only vertices of the graph can be ranked, and only ranks below the order have a bit.
+                match rank_unbounded(vert)
+                    .filter(|&rank| rank < graph.order() as u64 && graph.contains(&vert))
+                {
+                    Some(rank) => checks.ranked.push((rank, position)),
+                    None if checks.out_of_bounds.len() < REPORTED => {
+                        checks.out_of_bounds.push((position, vert))
+                    }
+                    None => {}
+                }
+                checks
+            })
+            .reduce(Checks::default, Checks::merge);
+        let mut ranked = std::mem::take(&mut checks.ranked);
+        ranked.par_sort_unstable();
+        for (rank, position) in ranked {
+            let (word, bit) = ((rank / 64) as usize, 1 << (rank % 64));
+            if self.visited[word] & bit != 0 {
+                checks
+                    .duplicates
+                    .push((batch[position - base], vec![position]));
+            }
+            self.visited[word] |= bit;
+        }
+        self.checks = std::mem::take(&mut self.checks).merge(checks);
+        self.len += self.batch.len();
+        self.last = self.batch.last().copied().or(self.last);
+        self.batch.clear();
+    }
+
+    /// Check what is left and report. A duplicate is reported with the positions at which it was visited again, not its first.
+    pub fn report(mut self) -> CertifyReport {
+        self.check_batch();
+        let closed = self.len > 1
+            && self
+                .last
+                .zip(self.first)
+                .is_some_and(|(last, first)| last.is_adj_to(first));
+        let Checks {
+            duplicates,
+            out_of_bounds,
+            not_adjacent,
+            displacement_sum,
+            ..
+        } = self.checks;
+        let id = match duplicates.is_empty()
+            && self.len == self.graph.order()
+            && out_of_bounds.is_empty()
+            && displacement_sum == [0, 0, 0]
+            && not_adjacent.is_none()
+        {
+            true if closed => SequenceID::HamCycle,
+            true => SequenceID::HamChain,
+            false => SequenceID::Broken,
+        };
+        CertifyReport {
+            id,
+            len: self.len,
+            order: self.graph.order(),
+            duplicates,
+            out_of_bounds,
+            not_adjacent,
+            closed,
+            displacement_sum,
+        }
+    }
+}
+
+impl TourSink for StreamCertifier {
+    fn put(&mut self, vert: V3d) -> Result<(), WeaveError> {
+        self.batch_put(vert);
+        Ok(())
+    }
+}
+
+/// Certify the tour yielded by verts without collecting it.
+pub fn certify_stream(
+    verts: impl IntoIterator<Item = V3d>,
+    order: Count,
+    max_absumv3d: ScalarXyz,
+) -> CertifyReport {
+    let mut certifier = StreamCertifier::new(order, max_absumv3d);
+    verts.into_iter().for_each(|vert| certifier.batch_put(vert));
+    certifier.report()
+}
+
+/// Certify the tour in the `.csv` file at `file_path`, as written by `SerializeToCsv` or `CsvSink`, without loading it.
+/// The first vertex repeated at the end to close the loop is not part of the tour.
+pub fn certify_csv(
+    file_path: &str,
+    order: Count,
+    max_absumv3d: ScalarXyz,
+) -> Result<CertifyReport, WeaveError> {
+    let mut certifier = StreamCertifier::new(order, max_absumv3d);
+    let mut pending = None;
+    for row in csv::Reader::from_path(file_path)?.into_deserialize::<CsvVector>() {
+        let CsvVector { x, y, z } = row?;
+        if let Some(vert) = pending.replace([x, y, z]) {
+            certifier.batch_put(vert);
+        }
+    }
+    if let Some(vert) = pending.filter(|&vert| Some(vert) != certifier.first) {
+        certifier.batch_put(vert);
+    }
+    Ok(certifier.report())
+}
+
+/// Certify the tour in the `.wvt` file at `file_path` as a tour of the discocube n of its header, without loading it.
+/// A file whose checksum doesn't match is an error rather than a report.
+pub fn certify_wvt(file_path: &str) -> Result<CertifyReport, WeaveError> {
+    let reader = WvtReader::open(file_path)?;
+    let n = reader.header().n as usize;
+    check_n(n)?;
+    let mut certifier = StreamCertifier::new(n.get_order_from_n(), n.get_max_absumv());
+    for vert in reader {
+        certifier.batch_put(vert?);
+    }
+    Ok(certifier.report())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::{
+        ops::certify_solution::Certify,
+        sink::CsvSink,
+        weave::{weave, weave_into},
+        wvt::WvtSink,
+    };
+
+    /// 🩺 Streaming certifies the same as `certify_report` on the first weaves, also with a batch boundary in the middle of the tour.
+    #[test]
+    fn test_certify_stream() {
+        for n in 1..=30 {
+            let (order, max_absumv) = (n.get_order_from_n(), n.get_max_absumv());
+            let solution = weave(n).unwrap();
+            let report = certify_stream(solution.iter().copied(), order, max_absumv);
+            assert_eq!(report, solution.certify_report(order, max_absumv));
+            let mut certifier = StreamCertifier::new(order, max_absumv);
+            weave_into(n, &mut certifier).unwrap();
+            assert_eq!(certifier.report().id, SequenceID::HamCycle);
+        }
+        let n = 100;
+        let order = n.get_order_from_n();
+        assert!(order > BATCH);
+        let mut solution = weave(n).unwrap().into_tour();
+        assert_eq!(
+            certify_stream(solution.iter().copied(), order, n.get_max_absumv()).id,
+            SequenceID::HamCycle
+        );
+        solution.swap(BATCH, BATCH + 1);
+        let report = certify_stream(solution.iter().copied(), order, n.get_max_absumv());
+        assert_eq!(report.not_adjacent, Some((BATCH - 1, BATCH)));
+    }
+
+    /// 🩺 Broken tours are reported like `certify_report` does, except a duplicate is only at the positions it was visited again.
+    #[test]
+    fn test_certify_stream_broken() {
+        let mut sol: Solution = vec![
+            [1, 1, -1],
+            [-1, -1, 1],
+            [-1, 1, 1],
+            [1, 1, 1],
+            [1, -1, 1],
+            [1, -1, -1],
+            [-1, -1, -1],
+            [-1, 1, -1],
+        ];
+        let report = certify_stream(sol.iter().copied(), 8, 3);
+        assert_eq!(report, sol.certify_report(8, 3));
+        assert_eq!(report.to_string(), "Broken | not adjacent at 0 and 1");
+        sol[1] = [1, 1, -1];
+        sol.extend([[-1, -1, -9], [0, 1, -1]]);
+        let report = certify_stream(sol.iter().copied(), 8, 3);
+        assert_eq!(report.duplicates, vec![([1, 1, -1], vec![1])]);
+        assert_eq!(
+            report.out_of_bounds,
+            vec![(8, [-1, -1, -9]), (9, [0, 1, -1])]
+        );
+        assert_eq!((report.len, report.displacement_sum), (10, [1, 2, -12]));
+        assert_eq!(report.id, SequenceID::Broken);
+        // A vertex within the L1-norm given but ranked past the order given is out of bounds too.
+        let report = certify_stream([[1, 1, -1], [1, 1, 5], [1, 1, -1]], 8, 9);
+        assert_eq!(report.out_of_bounds, vec![(1, [1, 1, 5])]);
+        assert_eq!(report.duplicates, vec![([1, 1, -1], vec![2])]);
+    }
+
+    /// 🩺 Certify a tour written to csv, closing vertex included, without loading it.
+    #[test]
+    fn test_certify_csv() {
+        let n = 5;
+        let file_path = "test_certify_csv.csv";
+        weave_into(n, &mut CsvSink::create(file_path).unwrap()).unwrap();
+        let report = certify_csv(file_path, n.get_order_from_n(), n.get_max_absumv());
+        std::fs::remove_file(file_path).unwrap();
+        assert_eq!(report.unwrap().id, SequenceID::HamCycle);
+        assert!(certify_csv(file_path, 8, 3).is_err());
+    }
+
+    /// 🩺 Certify a tour written to `.wvt` as a tour of the n of its header, without loading it.
+    #[test]
+    fn test_certify_wvt() {
+        let file_path = "test_certify_wvt.wvt";
+        weave_into(5, &mut WvtSink::create(file_path, 5).unwrap()).unwrap();
+        let report = certify_wvt(file_path);
+        std::fs::remove_file(file_path).unwrap();
+        assert_eq!(report.unwrap().id, SequenceID::HamCycle);
+        assert!(certify_wvt(file_path).is_err());
+    }
+}
@@ -0,0 +1,138 @@
+use std::{error::Error, fmt};
+
+use super::{
+    ops::certify_solution::CertifyReport,
+    subtours::Obstruction,
+    types::{Count, Edge, ScalarXyz, V3d},
+};
+
+/// 🚨 Errors surfaced by `weave()` instead of aborting the whole process, so a batch run over many n can report and skip a failing instance.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum WeaveError {
+    /// n must be at least 1: the smallest discocube is the cube with 8 vertices.
+    InvalidN(usize),
+    /// n is too large for the coordinates to be represented by `ScalarXyz` without wrapping.
+    CoordinateOverflow { n: usize, max_n: usize },
+    /// No bridge edge was found at the predicted elevation z while merging cycles.
+    MissingBridge { z: ScalarXyz },
+    /// The ends of a bridge are not both in the sequence being aligned to it.
+    MisalignedWarp,
+    /// Two vertices which don't differ along exactly one axis do not make an edge.
+    InvalidEdge(Edge),
+    /// A thread end has no continuation on the yarn of the level z above it.
+    MissingPin { z: ScalarXyz },
+    /// Writing the tour to a sink failed.
+    Io(String),
+    /// The count is not the order of any discocube: 8, 32, 80, 160, ...
+    NotAnOctahedralOrder(usize),
+    /// A value computed from the given n or order doesn't fit in its type.
+    ArithmeticOverflow(usize),
+    /// The tour failed certification as a Hamiltonian cycle, the report says which checks failed.
+    NotAHamCycle(Box<CertifyReport>),
+    /// A vertex with more than two edges can't be on a subtour.
+    BranchingVertex(V3d),
+    /// Merging subtours got stuck before one cycle was left.
+    MergeObstructed(Box<Obstruction>),
+    /// The tour has no position k: it has only `order` vertices.
+    PositionOutOfRange { k: Count, order: Count },
+    /// The vertex is not a vertex of the discocube so it's not on the tour.
+    VertexNotInTour(V3d),
+    /// The vector is not a vertex of any discocube, or it is too far out to be ranked.
+    NotAVertex(V3d),
+    /// The rank of a vertex doesn't fit in a `Node`: the discocube has more than `u32::MAX` vertices.
+    RankOverflow(u64),
+    /// The file read is not a `.wvt` file this version can read, or holds a step that is not a direction code.
+    InvalidWvt(String),
+    /// The direction codes and the header read don't hash to the checksum of the header.
+    ChecksumMismatch { expected: u64, found: u64 },
+    /// The file read doesn't hold a sequence of vertices.
+    MalformedTour(String),
+}
+
+/// impl Display to print out WeaveError w/o debug.
+impl fmt::Display for WeaveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WeaveError::InvalidN(n) => write!(f, "Invalid n: {n}. n must be 1 or greater"),
+            WeaveError::CoordinateOverflow { n, max_n } => {
+                write!(f, "Coordinate overflow: n {n} is larger than max n {max_n}")
+            }
+            WeaveError::MissingBridge { z } => write!(f, "Missing bridge at level z {z}"),
+            WeaveError::MisalignedWarp => write!(f, "Misaligned warp: bridge not found in cycle"),
+            WeaveError::InvalidEdge((lhs, rhs)) => write!(f, "Invalid edge: {lhs:?} {rhs:?}"),
+            WeaveError::MissingPin { z } => write!(f, "Missing pin at level z {z}"),
+            WeaveError::Io(error) => write!(f, "Io: {error}"),
+            WeaveError::NotAnOctahedralOrder(order) => {
+                write!(
+                    f,
+                    "Not an octahedral order: {order} is not the order of any discocube"
+                )
+            }
+            WeaveError::ArithmeticOverflow(value) => {
+                write!(
+                    f,
+                    "Arithmetic overflow: values computed from {value} don't fit"
+                )
+            }
+            WeaveError::NotAHamCycle(report) => write!(f, "Not a Hamiltonian cycle: {report}"),
+            WeaveError::BranchingVertex(vert) => {
+                write!(f, "Branching vertex: {vert:?} has more than two edges")
+            }
+            WeaveError::MergeObstructed(obstruction) => {
+                write!(f, "Merge obstructed: {obstruction}")
+            }
+            WeaveError::PositionOutOfRange { k, order } => {
+                write!(
+                    f,
+                    "Position out of range: {k} is past the {order} vertices of the tour"
+                )
+            }
+            WeaveError::VertexNotInTour(vert) => {
+                write!(
+                    f,
+                    "Vertex not in tour: {vert:?} is not a vertex of the discocube"
+                )
+            }
+            WeaveError::NotAVertex(vert) => {
+                write!(f, "Not a vertex: {vert:?} can't be ranked")
+            }
+            WeaveError::RankOverflow(rank) => {
+                write!(f, "Rank overflow: rank {rank} doesn't fit in a node")
+            }
+            WeaveError::InvalidWvt(reason) => write!(f, "Invalid .wvt: {reason}"),
+            WeaveError::MalformedTour(reason) => write!(f, "Malformed tour: {reason}"),
+            WeaveError::ChecksumMismatch { expected, found } => {
+                write!(
+                    f,
+                    "Checksum mismatch: expected {expected:#018x}, found {found:#018x}"
+                )
+            }
+        }
+    }
+}
+
+impl Error for WeaveError {}
+
+/// Errors of the writers the tour is streamed to.
+impl From<std::io::Error> for WeaveError {
+    fn from(error: std::io::Error) -> Self {
+        WeaveError::Io(error.to_string())
+    }
+}
+
+/// Errors parsing a json tour.
+impl From<serde_json::Error> for WeaveError {
+    fn from(error: serde_json::Error) -> Self {
+        match error.classify() {
+            serde_json::error::Category::Io => WeaveError::Io(error.to_string()),
+            _ => WeaveError::MalformedTour(error.to_string()),
+        }
+    }
+}
+
+/// Errors of the csv writer the tour is streamed to.
+impl From<csv::Error> for WeaveError {
+    fn from(error: csv::Error) -> Self {
+        WeaveError::Io(error.to_string())
+    }
+}
@@ -2,9 +2,10 @@
 pub mod graph_types {
     use std::collections::{HashMap, HashSet};
 
+    pub use crate::graph::types::ScalarXyz;
+
     pub type Adjacency = HashMap<V3, Neighbors>;
     pub type Neighbors = HashSet<V3>;
-    pub type ScalarXyz = i16;
     pub type V2 = [ScalarXyz; 2];
     pub type V3 = [ScalarXyz; 3];
     pub type Vert = (ScalarXyz, ScalarXyz, ScalarXyz);
@@ -30,23 +31,23 @@ pub mod make {
     use crate::graph::ops::{certify_solution::L1Norm, graph_info_from_n::*};
 
     /// make graph with an xs_adjacency for zlevel == =1.
-    pub fn make_z_graph(n: usize) -> (usize, ZAdjacency, ZOrder, i16) {
+    pub fn make_z_graph(n: usize) -> (usize, ZAdjacency, ZOrder, ScalarXyz) {
         let order = n.get_order_from_n();
-        let max_xyz = order.get_radius_from_order() as i16;
+        let max_xyz = order.get_radius_from_order();
         let (z_adj, z_order) = make_xs_adjacency(n, max_xyz);
         (order, z_adj, z_order, max_xyz - 4)
     }
 
     /// An even smaller graph without an adjacency.
-    pub fn make_xs_graph(n: usize) -> (usize, ZOrder, i16) {
+    pub fn make_xs_graph(n: usize) -> (usize, ZOrder, ScalarXyz) {
         let order = n.get_order_from_n();
-        let max_xyz = n.get_radius_from_order() as i16;
+        let max_xyz = n.get_radius_from_order();
         let z_order = get_zlevel_order(n);
         (order, z_order, max_xyz - 4)
     }
 
     /// An adjacency list of only where the points scalar value for z is -1.
-    fn make_xs_adjacency(n: usize, max_xyz: i16) -> (ZAdjacency, ZOrder) {
+    fn make_xs_adjacency(n: usize, max_xyz: ScalarXyz) -> (ZAdjacency, ZOrder) {
         let adj = make_z_adjacency_map(max_xyz);
         (adj, get_zlevel_order(n))
     }
@@ -70,7 +71,7 @@ pub mod make {
     }
 
     /// Make vertices based on max_xyz.
-    fn vertices_for_z_adjacency(max_xyz: ScalarXyz) -> Vec<[i16; 2]> {
+    fn vertices_for_z_adjacency(max_xyz: ScalarXyz) -> Vec<V2> {
         let max_xyz_plus_1 = max_xyz + 1;
         iproduct!(
             (-max_xyz..=max_xyz).step_by(2),
@@ -83,9 +84,9 @@ pub mod make {
     }
 
     /// Get a list of tuples of zlevel and number of vertices whose z scalar value is zlevel.
-    fn get_zlevel_order(n: usize) -> Vec<(i16, usize)> {
+    fn get_zlevel_order(n: usize) -> Vec<(ScalarXyz, usize)> {
         zip(
-            (-((n * 2 - 1) as i16)..=-1).step_by(2),
+            (-((n * 2 - 1) as ScalarXyz)..=-1).step_by(2),
             (1..=n).map(|_n| 2 * _n * (_n + 1)),
         )
         .collect()
@@ -94,13 +95,13 @@ pub mod make {
     /// Make an adjacency list based on n.
     pub fn make_adjacency(n: usize) -> Adjacency {
         let order = n.get_order_from_n();
-        let max_xyz = order.get_radius_from_order() as i16;
-        let verts: Vec<[i16; 3]> = vertices(max_xyz);
+        let max_xyz = order.get_radius_from_order();
+        let verts: Vec<V3> = vertices(max_xyz);
         adjacency_map(&verts, max_xyz + 2)
     }
 
     /// Makes vertices based on max_xyz.
-    fn vertices(max_xyz: ScalarXyz) -> Vec<[i16; 3]> {
+    fn vertices(max_xyz: ScalarXyz) -> Vec<V3> {
         let max_xyz_plus_4 = max_xyz + 4;
         iproduct!(
             (-max_xyz..=max_xyz).step_by(2),
@@ -132,7 +133,7 @@ pub mod make {
     }
 
     /// Create a set of points of vert shifted in all 6 directions for 3d.
-    pub fn shift_xyz(vert: Array2<ScalarXyz>) -> Vec<[i16; 3]> {
+    pub fn shift_xyz(vert: Array2<ScalarXyz>) -> Vec<V3> {
         (vert
             + arr2(&[
                 [2, 0, 0],
@@ -148,7 +149,7 @@ pub mod make {
     }
 
     /// Create a set of points of vert shifted in all 4 directions for 2d.
-    pub fn shift_xy(vert: Array2<ScalarXyz>) -> Vec<[i16; 2]> {
+    pub fn shift_xy(vert: Array2<ScalarXyz>) -> Vec<V2> {
         (vert + arr2(&[[2, 0], [-2, 0], [0, 2], [0, -2]]))
             .outer_iter()
             .map(|point| [point[0], point[1]])
@@ -156,22 +157,80 @@ pub mod make {
     }
 }
 
-/// An iterator of the uncentered octahedral numbers.
+/// Iterate over and look up the uncentered octahedral numbers: the orders of the discocubes 8, 32, 80, 160, ...
 pub mod iters {
-    /// An iterator of the uncentered octahedral numbers.
-    pub fn uon(start: usize, end: usize) -> impl Iterator<Item = usize> {
-        (0..2000 + 2).filter_map(move |i| {
-            let _uon = (0..2000 * 2 + 2)
-                .step_by(2)
-                .take(i)
-                .map(|n| n * (n + 2))
-                .sum();
-            if _uon >= start && _uon <= end {
-                Some(_uon)
-            } else {
-                None
+    use crate::graph::ops::graph_info_from_n::{icbrt, three_quarters};
+    use std::ops::{RangeInclusive, Sub};
+
+    /// Integer types whose values can be orders of discocubes, computed with the closed form of `InfoN::get_order_from_n`.
+    pub trait Uon: Copy + Ord + Sub<Output = Self> {
+        /// Discocube n = 1, the smallest.
+        const ONE: Self;
+        /// The order of discocube n, `4 * n * (n + 1) * (n + 2) / 3`, if it fits.
+        fn order_of(n: Self) -> Option<Self>;
+        /// The largest n whose order is at most self, 0 if self is less than 8.
+        fn floor_n(self) -> Self;
+        /// n + 1, if it fits.
+        fn succ(self) -> Option<Self>;
+    }
+
+    macro_rules! impl_uon {
+        ($($int:ty),*) => {$(
+            impl Uon for $int {
+                const ONE: $int = 1;
+
+                fn order_of(n: $int) -> Option<$int> {
+                    // The product of three consecutive integers is a multiple of 3.
+                    let product = n.checked_mul(n.checked_add(1)?)?.checked_mul(n.checked_add(2)?)?;
+                    (product / 3).checked_mul(4)
+                }
+
+                fn floor_n(self) -> $int {
+                    // n * (n + 1) * (n + 2) <= 3 * order / 4 and n^3 < n * (n + 1) * (n + 2) < (n + 1)^3: n is the cube root or one less.
+                    let root = icbrt(three_quarters(self as u128)) as $int;
+                    match Self::order_of(root).is_some_and(|order| order <= self) {
+                        true => root,
+                        false => root - 1,
+                    }
+                }
+
+                fn succ(self) -> Option<$int> {
+                    self.checked_add(1)
+                }
             }
-        })
+        )*};
+    }
+
+    impl_uon!(usize, u64, u128);
+
+    /// The uncentered octahedral numbers within range, in increasing order, up to the largest that fits.
+    pub fn uon_range<T: Uon>(range: RangeInclusive<T>) -> impl Iterator<Item = T> {
+        let (start, end) = range.into_inner();
+        std::iter::successors(Some(start.floor_n().max(T::ONE)), |n| n.succ())
+            .map_while(T::order_of)
+            .skip_while(move |&order| order < start)
+            .take_while(move |&order| order <= end)
+    }
+
+    /// Whether order is the order of a discocube.
+    pub fn is_uon<T: Uon>(order: T) -> bool {
+        let n = order.floor_n();
+        n >= T::ONE && T::order_of(n) == Some(order)
+    }
+
+    /// The order of a discocube closest to order. Ties go to the smaller one.
+    pub fn nearest_uon<T: Uon>(order: T) -> T {
+        let n = order.floor_n().max(T::ONE);
+        let below = T::order_of(n).expect("the order of the floor of an order fits");
+        match n.succ().and_then(T::order_of) {
+            Some(above) if below < order && above - order < order - below => above,
+            _ => below,
+        }
+    }
+
+    /// An iterator of the uncentered octahedral numbers between start and end.
+    pub fn uon(start: usize, end: usize) -> impl Iterator<Item = usize> {
+        uon_range(start..=end)
     }
 }
 
@@ -181,6 +240,7 @@ pub mod debug {
     use std::fmt;
 
     /// Struct for timestamp.
+    #[allow(dead_code)]
     struct Timestamp(String);
 
     /// impl display for timestamp
@@ -223,3 +283,50 @@ pub mod debug {
         sizes
     }
 }
+
+/// 🩺 Test the uncentered octahedral numbers against the orders from InfoN.
+#[cfg(test)]
+mod tests_iters {
+    use super::iters::*;
+    use crate::graph::ops::graph_info_from_n::InfoN;
+
+    #[test]
+    fn test_uon_range() {
+        let orders = (1..=3000)
+            .map(|n: usize| n.get_order_from_n())
+            .collect::<Vec<_>>();
+        assert_eq!(uon_range(0..=orders[2999]).collect::<Vec<_>>(), orders);
+        assert_eq!(uon_range(9_usize..=160).collect::<Vec<_>>(), [32, 80, 160]);
+        assert_eq!(uon_range(33_u64..=79).count(), 0);
+        assert_eq!(uon(1, 100).collect::<Vec<_>>(), [8, 32, 80]);
+        // The last orders that fit.
+        assert_eq!(uon_range(u64::MAX - 1..=u64::MAX).count(), 0);
+        let last = u128::MAX.floor_n();
+        let (before_last, last_order) = (
+            <u128 as Uon>::order_of(last - 1).unwrap(),
+            <u128 as Uon>::order_of(last).unwrap(),
+        );
+        assert_eq!(<u128 as Uon>::order_of(last + 1), None);
+        assert_eq!(
+            uon_range(before_last..=u128::MAX).collect::<Vec<_>>(),
+            [before_last, last_order]
+        );
+    }
+
+    #[test]
+    fn test_is_and_nearest_uon() {
+        assert!(!is_uon(0_u64));
+        assert!(is_uon(8_u64) && is_uon(32_u128) && is_uon(1_373_600_usize));
+        assert!(!is_uon(31_u64) && !is_uon(33_u64));
+        assert_eq!(nearest_uon(0_u64), 8);
+        assert_eq!(nearest_uon(20_u64), 8);
+        assert_eq!(nearest_uon(21_u64), 32);
+        assert_eq!(nearest_uon(1_373_601_u64), 1_373_600);
+        let n: u128 = 1 << 40;
+        let order = <u128 as Uon>::order_of(n).unwrap();
+        assert!(is_uon(order) && !is_uon(order + 1));
+        assert_eq!(order.floor_n(), n);
+        assert_eq!((order - 1).floor_n(), n - 1);
+        assert_eq!(nearest_uon(order + 1), order);
+    }
+}
@@ -0,0 +1,273 @@
+use std::{fs::File, io::BufReader, path::Path};
+
+use serde::Deserialize;
+
+use super::{
+    certify_stream::{certify_csv, certify_wvt},
+    error::WeaveError,
+    ops::{
+        certify_solution::{Certify, CertifyReport, SequenceID},
+        graph_info_from_n::{CheckedInfoN, InfoN},
+        serialize_csv::CsvVector,
+    },
+    types::*,
+    weave::check_n,
+    wvt::WvtReader,
+};
+
+/// A vertex in a json tour: `[x, y, z]` or `{"X": x, "Y": y, "Z": z}`, lowercase keys too.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum JsonVertex {
+    Array(V3d),
+    Object {
+        #[serde(alias = "x")]
+        #[serde(rename = "X")]
+        x: ScalarXyz,
+        #[serde(alias = "y")]
+        #[serde(rename = "Y")]
+        y: ScalarXyz,
+        #[serde(alias = "z")]
+        #[serde(rename = "Z")]
+        z: ScalarXyz,
+    },
+}
+
+/// 📥 Read a tour back from the files written by `SerializeToCsv`, `CsvSink`, `BinarySink` and `WvtSink`, or by other tools.
+/// The first vertex repeated at the end to close the loop, as the csv files have it, is not part of the tour.
+/// ```ignore
+/// let solution = Solution::from_csv("documents/solutions/csv/solution_2.csv")?;
+/// ```
+pub trait ReadTour: Sized {
+    /// Read `X,Y,Z` rows with a header.
+    fn from_csv(file_path: &str) -> Result<Self, WeaveError>;
+    /// Read a json array of vertices, each `[x, y, z]` or `{"X": x, "Y": y, "Z": z}`.
+    fn from_json(file_path: &str) -> Result<Self, WeaveError>;
+    /// Read the raw little-endian `ScalarXyz` triples written by `BinarySink`.
+    fn from_binary(file_path: &str) -> Result<Self, WeaveError>;
+    /// Read a `.wvt` file, checking its checksum.
+    fn from_wvt(file_path: &str) -> Result<Self, WeaveError>;
+    /// Read the file in the format given by its extension: `.csv`, `.json`, `.wvt`, anything else is raw binary.
+    fn from_file(file_path: &str) -> Result<Self, WeaveError> {
+        match Path::new(file_path)
+            .extension()
+            .and_then(|ext| ext.to_str())
+        {
+            Some("csv") => Self::from_csv(file_path),
+            Some("json") => Self::from_json(file_path),
+            Some("wvt") => Self::from_wvt(file_path),
+            _ => Self::from_binary(file_path),
+        }
+    }
+}
+
+impl ReadTour for Solution {
+    fn from_csv(file_path: &str) -> Result<Solution, WeaveError> {
+        let tour = csv::Reader::from_path(file_path)?
+            .into_deserialize::<CsvVector>()
+            .map(|row| row.map(|CsvVector { x, y, z }| [x, y, z]))
+            .collect::<Result<Solution, _>>()?;
+        Ok(unclosed(tour))
+    }
+
+    fn from_json(file_path: &str) -> Result<Solution, WeaveError> {
+        let verts: Vec<JsonVertex> =
+            serde_json::from_reader(BufReader::new(File::open(file_path)?))?;
+        let tour = verts
+            .into_iter()
+            .map(|vert| match vert {
+                JsonVertex::Array(vert) => vert,
+                JsonVertex::Object { x, y, z } => [x, y, z],
+            })
+            .collect();
+        Ok(unclosed(tour))
+    }
+
+    fn from_binary(file_path: &str) -> Result<Solution, WeaveError> {
+        const SCALAR: usize = std::mem::size_of::<ScalarXyz>();
+        let bytes = std::fs::read(file_path)?;
+        if bytes.len() % (3 * SCALAR) != 0 {
+            return Err(WeaveError::MalformedTour(format!(
+                "{} bytes is not a whole number of {}-byte vertices",
+                bytes.len(),
+                3 * SCALAR
+            )));
+        }
+        let scalar = |chunk: &[u8]| ScalarXyz::from_le_bytes(chunk.try_into().unwrap());
+        let tour = bytes
+            .chunks_exact(3 * SCALAR)
+            .map(|vert| [0, 1, 2].map(|axis| scalar(&vert[axis * SCALAR..(axis + 1) * SCALAR])))
+            .collect();
+        Ok(unclosed(tour))
+    }
+
+    fn from_wvt(file_path: &str) -> Result<Solution, WeaveError> {
+        WvtReader::open(file_path)?.into_tour()
+    }
+}
+
+/// Drop the first vertex repeated at the end to close the loop, if it is.
+fn unclosed(mut tour: Tour) -> Tour {
+    if tour.len() > 1 && tour.first() == tour.last() {
+        tour.pop();
+    }
+    tour
+}
+
+/// ✅ Certify the tour in the file at `file_path` as a Hamiltonian cycle of discocube n, returning the report of the checks it passed.
+/// Without n it is inferred from the number of vertices read, which must be the order of a discocube, or from the header of a `.wvt` file.
+/// `.csv` and `.wvt` files are certified as they stream by with `certify_csv` and `certify_wvt`, the other formats are read whole.
+/// A broken tour is `WeaveError::NotAHamCycle` with the report of what failed.
+pub fn verify_file(file_path: &str, n: Option<usize>) -> Result<CertifyReport, WeaveError> {
+    let report = match Path::new(file_path)
+        .extension()
+        .and_then(|ext| ext.to_str())
+    {
+        Some("csv") => {
+            let n = match n {
+                Some(n) => n,
+                None => csv_order(file_path)?.try_n_from_order()?,
+            };
+            check_n(n)?;
+            certify_csv(file_path, n.get_order_from_n(), n.get_max_absumv())?
+        }
+        Some("wvt") => {
+            let header_n = WvtReader::open(file_path)?.header().n;
+            if let Some(n) = n.filter(|&n| n as u64 != header_n) {
+                return Err(WeaveError::InvalidWvt(format!(
+                    "the tour is of n {header_n}, not of n {n}"
+                )));
+            }
+            certify_wvt(file_path)?
+        }
+        _ => {
+            let tour = Solution::from_file(file_path)?;
+            let n = match n {
+                Some(n) => n,
+                None => tour.len().try_n_from_order()?,
+            };
+            check_n(n)?;
+            tour.certify_report(n.get_order_from_n(), n.get_max_absumv())
+        }
+    };
+    match report.id {
+        SequenceID::HamCycle => Ok(report),
+        _ => Err(WeaveError::NotAHamCycle(Box::new(report))),
+    }
+}
+
+/// Number of vertices of the tour in the csv file at `file_path`, counted without holding them. The closing vertex isn't counted.
+fn csv_order(file_path: &str) -> Result<usize, WeaveError> {
+    let (mut len, mut first, mut last) = (0, None, None);
+    for row in csv::Reader::from_path(file_path)?.into_deserialize::<CsvVector>() {
+        let CsvVector { x, y, z } = row?;
+        first.get_or_insert([x, y, z]);
+        last = Some([x, y, z]);
+        len += 1;
+    }
+    Ok(match len > 1 && first == last {
+        true => len - 1,
+        false => len,
+    })
+}
+
+// 🩺 TEST
+//
+// 🩺 Test that the tours written by each writer are read back and verified.
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::{
+        ops::serialize_csv::SerializeToCsv,
+        sink::{BinarySink, TourSink},
+        weave::weave,
+        wvt::SerializeToWvt,
+    };
+
+    #[test]
+    /// Csv with its closing vertex, json both ways, raw binary and wvt read back to the same tour and verify.
+    fn test_read_tour() {
+        let solution = weave(4).unwrap();
+        let prefix = "test_read_tour";
+        let csv = format!("{prefix}.csv");
+        solution.serialize_to_csv(&csv).unwrap();
+        let json = format!("{prefix}.json");
+        std::fs::write(&json, serde_json::to_string(&*solution).unwrap()).unwrap();
+        let objects = format!("{prefix}_objects.json");
+        let rows = solution
+            .iter()
+            .map(|[x, y, z]| format!(r#"{{"x": {x}, "Y": {y}, "z": {z}}}"#))
+            .collect::<Vec<_>>();
+        std::fs::write(&objects, format!("[{}]", rows.join(","))).unwrap();
+        let binary = format!("{prefix}.bin");
+        let mut sink = BinarySink::new(File::create(&binary).unwrap());
+        solution
+            .iter()
+            .try_for_each(|&vert| sink.put(vert))
+            .unwrap();
+        sink.finish().unwrap();
+        let wvt = format!("{prefix}.wvt");
+        solution.serialize_to_wvt(&wvt).unwrap();
+        for file_path in [&csv, &json, &objects, &binary, &wvt] {
+            assert_eq!(Solution::from_file(file_path).unwrap(), *solution);
+            for n in [None, Some(4)] {
+                let report = verify_file(file_path, n).unwrap();
+                assert_eq!((report.id, report.order), (SequenceID::HamCycle, 160));
+            }
+            std::fs::remove_file(file_path).unwrap();
+        }
+    }
+
+    #[test]
+    /// The wrong n, a count that is no order, a broken tour and a malformed file are all errors.
+    fn test_verify_file() {
+        let file_path = "test_verify_file.json";
+        let mut tour = weave(2).unwrap().into_tour();
+        std::fs::write(file_path, serde_json::to_string(&tour).unwrap()).unwrap();
+        assert!(matches!(
+            verify_file(file_path, Some(3)),
+            Err(WeaveError::NotAHamCycle(_))
+        ));
+        tour.swap(3, 4);
+        std::fs::write(file_path, serde_json::to_string(&tour).unwrap()).unwrap();
+        assert!(matches!(
+            verify_file(file_path, None),
+            Err(WeaveError::NotAHamCycle(_))
+        ));
+        std::fs::write(file_path, serde_json::to_string(&tour[1..]).unwrap()).unwrap();
+        assert_eq!(
+            verify_file(file_path, None),
+            Err(WeaveError::NotAnOctahedralOrder(31))
+        );
+        std::fs::write(file_path, "[[1, 1]]").unwrap();
+        assert!(matches!(
+            verify_file(file_path, None),
+            Err(WeaveError::MalformedTour(_))
+        ));
+        std::fs::remove_file(file_path).unwrap();
+        let binary = "test_verify_file.bin";
+        std::fs::write(binary, [1, 0, 1]).unwrap();
+        assert!(matches!(
+            verify_file(binary, None),
+            Err(WeaveError::MalformedTour(_))
+        ));
+        std::fs::remove_file(binary).unwrap();
+        assert!(matches!(verify_file(binary, None), Err(WeaveError::Io(_))));
+        // Streamed: a csv certified against the wrong n, a wvt whose header says another n.
+        let solution = weave(2).unwrap();
+        let csv = "test_verify_file.csv";
+        solution.serialize_to_csv(csv).unwrap();
+        assert!(matches!(
+            verify_file(csv, Some(3)),
+            Err(WeaveError::NotAHamCycle(_))
+        ));
+        std::fs::remove_file(csv).unwrap();
+        let wvt = "test_verify_file.wvt";
+        solution.serialize_to_wvt(wvt).unwrap();
+        assert!(matches!(
+            verify_file(wvt, Some(3)),
+            Err(WeaveError::InvalidWvt(_))
+        ));
+        std::fs::remove_file(wvt).unwrap();
+    }
+}
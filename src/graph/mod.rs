@@ -1,4 +1,13 @@
+pub mod certify_stream;
+pub mod error;
 pub mod extras;
+pub mod import;
 pub mod ops;
+pub mod profile;
+pub mod query;
+pub mod sink;
+pub mod strands;
+pub mod subtours;
 pub mod types;
 pub mod weave;
+pub mod wvt;
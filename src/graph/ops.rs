@@ -2,18 +2,49 @@
 pub mod prelude {
     pub use super::super::types::*;
     pub use super::{
-        certify_solution::*, color_yarn::ColorSpunYarn, extend_threads::ExtendLoomThreads,
-        graph_info_from_n::InfoN, merge_cycles::*, mirror_loom::MirrorLoomThreads,
-        pin_threads::PinThreadEnds, prepare_yarn::PrepYarnExtensions, prepare_yarn::SegmentYarn,
+        certify_solution::*,
+        color_yarn::ColorSpunYarn,
+        extend_threads::ExtendLoomThreads,
+        graph_info_from_n::InfoN,
+        merge_cycles::*,
+        mirror_loom::*,
+        pin_threads::PinThreadEnds,
+        prepare_yarn::{chop_spans, PrepYarnExtensions, SegmentYarn, YarnCut},
         spin_yarn::Spin,
+        splice::*,
     };
 }
 
 /// ℹ️ Given n calculate values used for building the solution. What happens if you shrink the representation of a graph to almost nothing for the sake of saving memory.
 pub mod graph_info_from_n {
-    use crate::graph::types::*;
+    use crate::graph::{error::WeaveError, types::*};
     use itertools::Itertools;
-    use std::iter::{repeat, zip};
+    use std::iter::{repeat_n, zip};
+
+    /// Largest n whose weave fits in `ScalarXyz` and whose order fits in `Count`. Beyond it the values below silently wrap, so `weave()` refuses larger n up front.
+    /// The largest scalar computed is the sum of the z scalar values of both ends of an edge, which is compared while merging: `4 * n - 2`.
+    /// Select i32 coordinates with the `i32` feature to go past n = 8192.
+    pub const MAX_N: Count = {
+        let max_scalar_n = (ScalarXyz::MAX as Count + 2) / 4;
+        let max_order_n = max_n_for_order();
+        match max_scalar_n < max_order_n {
+            true => max_scalar_n,
+            false => max_order_n,
+        }
+    };
+
+    /// Largest n for which `4 * n * (n + 1) * (n + 2)`, the numerator of the order, fits in `Count`.
+    const fn max_n_for_order() -> Count {
+        let (mut fits, mut overflows): (Count, Count) = (1, 1 << 21);
+        while overflows - fits > 1 {
+            let n = (fits + overflows) / 2;
+            match (4 * n).checked_mul(n + 1) {
+                Some(m) if m.checked_mul(n + 2).is_some() => fits = n,
+                _ => overflows = n,
+            }
+        }
+        fits
+    }
 
     /// Given n, get required calculations/settings needed for the weave.
     pub trait InfoN {
@@ -55,11 +86,11 @@ pub mod graph_info_from_n {
         }
 
         fn get_radius_from_order(self) -> ScalarXyz {
-            (((3 * self) / 4) as f64).powf(1.0 / 3.0) as i16 * 2 - 1
+            self.get_n_from_order() as ScalarXyz * 2 - 1
         }
 
         fn get_n_from_order(self) -> Count {
-            (((3 * self) / 4) as f64).powf(1.0 / 3.0) as Count
+            icbrt(three_quarters(self as u128)) as Count
         }
 
         fn get_order_from_n(self) -> Count {
@@ -73,10 +104,10 @@ pub mod graph_info_from_n {
         fn z_color_len(self) -> ZrowColorSize {
             zip(
                 zip(
-                    (-((self * 2 - 1) as i16)..=-1).step_by(2),
+                    (-((self * 2 - 1) as ScalarXyz)..=-1).step_by(2),
                     match self % 2 {
-                        0 => repeat(1).take(self).interleave(repeat(3).take(self)),
-                        _ => repeat(3).take(self).interleave(repeat(1).take(self)),
+                        0 => repeat_n(1, self).interleave(repeat_n(3, self)),
+                        _ => repeat_n(3, self).interleave(repeat_n(1, self)),
                     },
                 ),
                 (1..=self).map(|f| 2 * f * (f + 1)),
@@ -84,6 +115,113 @@ pub mod graph_info_from_n {
             .collect()
         }
     }
+
+    /// Same as `InfoN` but returning an error instead of wrapping when a value doesn't fit in its type, and inverting orders exactly.
+    pub trait CheckedInfoN: Sized {
+        /// Retrieve loom size based on n.
+        fn checked_loom_size(self) -> Result<Count, WeaveError>;
+        /// Get the maximum L1-norm for n.
+        fn checked_max_absumv(self) -> Result<ScalarXyz, WeaveError>;
+        /// Get maximum scalar value allowed for x, y or z.
+        fn checked_radius(self) -> Result<ScalarXyz, WeaveError>;
+        /// Get maximum scalar value allowed for x, y or z as a count.
+        fn checked_radius_usize(self) -> Result<usize, WeaveError>;
+        /// Get maximum scalar value allowed for x, y or z where n is the order.
+        fn checked_radius_from_order(self) -> Result<ScalarXyz, WeaveError>;
+        /// get level from order, which must be the order of a discocube.
+        fn try_n_from_order(self) -> Result<Count, WeaveError>;
+        /// get order from level/n.
+        fn checked_order_from_n(self) -> Result<Count, WeaveError>;
+        /// spool size is the number of verts in the graph whose z-scalar value is -1.
+        fn checked_spool_size(self) -> Result<Count, WeaveError>;
+        /// Get length of the current level.
+        fn checked_z_color_len(self) -> Result<ZrowColorSize, WeaveError>;
+    }
+
+    impl CheckedInfoN for Count {
+        fn checked_loom_size(self) -> Result<Count, WeaveError> {
+            // The loom only holds threads of n whose coordinates fit.
+            self.checked_radius()?;
+            (self / 2)
+                .checked_add(1)
+                .ok_or(WeaveError::ArithmeticOverflow(self))
+        }
+
+        fn checked_max_absumv(self) -> Result<ScalarXyz, WeaveError> {
+            to_scalar(self, self.checked_mul(2).and_then(|m| m.checked_add(1)))
+        }
+
+        fn checked_radius(self) -> Result<ScalarXyz, WeaveError> {
+            to_scalar(self, self.checked_radius_usize().ok())
+        }
+
+        fn checked_radius_usize(self) -> Result<usize, WeaveError> {
+            self.checked_mul(2)
+                .and_then(|m| m.checked_sub(1))
+                .ok_or(WeaveError::ArithmeticOverflow(self))
+        }
+
+        fn checked_radius_from_order(self) -> Result<ScalarXyz, WeaveError> {
+            self.try_n_from_order()?.checked_radius()
+        }
+
+        fn try_n_from_order(self) -> Result<Count, WeaveError> {
+            let n = self.get_n_from_order();
+            match n.checked_order_from_n() {
+                Ok(order) if n > 0 && order == self => Ok(n),
+                _ => Err(WeaveError::NotAnOctahedralOrder(self)),
+            }
+        }
+
+        fn checked_order_from_n(self) -> Result<Count, WeaveError> {
+            self.checked_add(2)
+                .and_then(|m| m.checked_mul(self + 1))
+                .and_then(|m| m.checked_mul(self))
+                .and_then(|m| m.checked_mul(4))
+                .map(|m| m / 3)
+                .ok_or(WeaveError::ArithmeticOverflow(self))
+        }
+
+        fn checked_spool_size(self) -> Result<Count, WeaveError> {
+            self.checked_add(1)
+                .and_then(|m| m.checked_mul(self))
+                .and_then(|m| m.checked_mul(2))
+                .ok_or(WeaveError::ArithmeticOverflow(self))
+        }
+
+        fn checked_z_color_len(self) -> Result<ZrowColorSize, WeaveError> {
+            self.checked_radius()?;
+            self.checked_spool_size()?;
+            Ok(self.z_color_len())
+        }
+    }
+
+    /// The value computed from n, if it was computed without overflowing, as a scalar.
+    fn to_scalar(n: Count, value: Option<Count>) -> Result<ScalarXyz, WeaveError> {
+        value
+            .and_then(|value| ScalarXyz::try_from(value).ok())
+            .ok_or(WeaveError::ArithmeticOverflow(n))
+    }
+
+    /// `3 * order / 4` without overflowing for any order: the order is `4 / 3 * n * (n + 1) * (n + 2)`.
+    /// Wide enough for the orders of every integer type `Uon` is implemented for.
+    pub fn three_quarters(order: u128) -> u128 {
+        order / 4 * 3 + order % 4 * 3 / 4
+    }
+
+    /// Integer cube root: the largest r with `r * r * r <= value`.
+    /// Starts from the float estimate, which may be off by one for large values, and corrects it.
+    pub fn icbrt(value: u128) -> u128 {
+        let cubed = |r: u128| r.checked_pow(3);
+        let mut root = (value as f64).cbrt() as u128;
+        while cubed(root).is_none_or(|cube| cube > value) {
+            root -= 1;
+        }
+        while cubed(root + 1).is_some_and(|cube| cube <= value) {
+            root += 1;
+        }
+        root
+    }
 }
 
 /// 🛞 Spin a zigzagging-inward-spiralling Hamiltonian chain from the outer to innermost vert where the scalar z value of the points equals -1.
@@ -95,7 +233,7 @@ mod spin_yarn {
     use crate::graph::types::*;
 
     /// 2d displacement vectors for walking a zig-zag inwards.
-    pub const DPYX_EVEN: [[[i16; 2]; 2]; 4] = [
+    pub const DPYX_EVEN: [[V2d; 2]; 4] = [
         [[0, 2], [2, 0]],
         [[0, 2], [-2, 0]],
         [[0, -2], [-2, 0]],
@@ -103,7 +241,7 @@ mod spin_yarn {
     ];
 
     /// 2d displacement vectors for walking a zig-zag inwards.
-    pub const DPYX_ODD: [[[i16; 2]; 2]; 4] = [
+    pub const DPYX_ODD: [[V2d; 2]; 4] = [
         [[0, -2], [-2, 0]],
         [[0, -2], [2, 0]],
         [[0, 2], [2, 0]],
@@ -123,7 +261,7 @@ mod spin_yarn {
             // r = (4 * 2) - 1
             // r = 7
             let r = n.get_radius_usize();
-            let (start, disp_vects) = if n % 2 == 0 {
+            let (start, disp_vects) = if n.is_multiple_of(2) {
                 ([1, 1], DPYX_EVEN)
             } else {
                 ([-1, 1], DPYX_ODD)
@@ -142,14 +280,12 @@ mod spin_yarn {
                 // and the second: `(idx + 1) % 2` results in [0, 1, 0, 1, ...] which alternates between the y and x displacement vector.
                 // [(0, 1), (1, 1), (2, 3), (3, 3), (4, 5), (5, 5), (6, 7), (7, 7), (8, 7)]
                 .flat_map(|(idx, len)| {
-                    (0..len)
-                        .map(move |i| disp_vects[idx % 4][(idx + i) % 2])
-                        // `[idx % 4]` => 0, 1, 2, 3
-                        // Cycles through the zigzag vectors.
-                        // `[(idx + i) % 2]` => 0, 1, 0, 1
-                        // replaces the infinite iterator of [0, 1] to access y or x displacement vector in the zigzag
-                        // displacement vector
-                        .into_iter()
+                    (0..len).map(move |i| disp_vects[idx % 4][(idx + i) % 2])
+                    // `[idx % 4]` => 0, 1, 2, 3
+                    // Cycles through the zigzag vectors.
+                    // `[(idx + i) % 2]` => 0, 1, 0, 1
+                    // replaces the infinite iterator of [0, 1] to access y or x displacement vector in the zigzag
+                    // displacement vector
                 })
                 // preface with start of path: [radius, 1]
                 .prefaced_with(start)
@@ -227,8 +363,8 @@ mod color_yarn {
     }
 
     impl ColorSpunYarn for Yarns {
-        fn color_spun(mut spool: Spindle) -> Yarns {
-            let blue = Yarn::from(spool.drain(..).collect::<Spindle>());
+        fn color_spun(spool: Spindle) -> Yarns {
+            let blue = Yarn::from(spool);
             let red = blue.dot(&array![[-1, 0], [0, -1]]) + array![[0, 2]];
             Yarns::from([(3, blue), (1, red)])
         }
@@ -267,7 +403,7 @@ pub mod prepost_iter {
         ///
         /// A new instance of the type with `item` prepended to it.
         fn prefaced_with(self, item: T) -> U {
-            once(item).chain(self.into_iter()).collect()
+            once(item).chain(self).collect()
         }
     }
 
@@ -301,7 +437,7 @@ pub mod prepost_iter {
         ///
         /// A new iterator that has `item` prepended to it.
         /// same as:
-        /// ```
+        /// ```ignore
         /// self.chain(once(item))
         /// ```
         fn postfaced_with(self, item: T) -> Chain<Self, Once<T>> {
@@ -339,7 +475,7 @@ pub mod prepost_iter {
         ///
         /// A new iterator that has `item` prepended to it.
         /// same as:
-        /// ```
+        /// ```ignore
         /// once(item).chain(self)
         /// ```
         fn prefaced_with(self, item: T) -> Chain<Once<T>, Self> {
@@ -366,11 +502,11 @@ pub mod pin_threads {
         /// resulting in: `[40, 30, 20, 10, 0, 1, 2, 3, 4, 5, 6, 16, 26]`
         /// Insert pins into each end of each thread in the loom. A pin is the vertex adjacent to and directly above an end. Collect the a copy of all inserted pins to be used for cutting the finished yarn from the next level up.
         ///
-        fn pin_thread_ends(&mut self, zrow: i16) -> PinCushion;
+        fn pin_thread_ends(&mut self, zrow: ScalarXyz) -> PinCushion;
     }
 
     impl PinThreadEnds for Loom {
-        fn pin_thread_ends(&mut self, zrow: i16) -> PinCushion {
+        fn pin_thread_ends(&mut self, zrow: ScalarXyz) -> PinCushion {
             self.iter_mut()
                 .flat_map(|t| {
                     let [[x, y, _], [i, j, _]] = [t[0], t[t.len() - 1]];
@@ -382,11 +518,11 @@ pub mod pin_threads {
 
     /// Add two pins, one for each end of the thread. Collect copy of each as a guide for cutting/segmenting and placing the yarn.
     pub trait AddPinsFrontBack {
-        fn add_pins(&mut self, front: [i16; 3], back: [i16; 3]) -> [[i16; 3]; 2];
+        fn add_pins(&mut self, front: V3d, back: V3d) -> [V3d; 2];
     }
 
     impl AddPinsFrontBack for LoomThread {
-        fn add_pins(&mut self, front: [i16; 3], back: [i16; 3]) -> [[i16; 3]; 2] {
+        fn add_pins(&mut self, front: V3d, back: V3d) -> [V3d; 2] {
             self.push_front(front);
             self.push_back(back);
             [front, back]
@@ -396,10 +532,12 @@ pub mod pin_threads {
 
 /// 👨‍🍳 Prepare yarn for extending onto the loom threads. Cut using pins and affix yarn to the current elevation.
 pub mod prepare_yarn {
+    use super::splice::Span;
     use crate::graph::types::*;
     use itertools::Itertools;
     use ndarray::s;
     use rayon::prelude::*;
+    use std::collections::HashSet;
 
     /// 👨‍🍳 Prepare yarn to be extennded onto the individual thread ends.
     pub trait PrepYarnExtensions {
@@ -417,11 +555,11 @@ pub mod prepare_yarn {
         /// Cut finished yarn if `pins` is not empty, `cut_yarn(_yarn, &pins)`. Return the finished (cut or not) yarn.
         ///
         ///
-        fn prep(&self, zpos: i16, color: u8, len: usize) -> Warp;
+        fn prep(&self, zpos: ScalarXyz, color: u8, len: usize) -> Warp;
     }
 
     impl PrepYarnExtensions for Yarns {
-        fn prep(&self, zpos: i16, color: u8, len: usize) -> Warp {
+        fn prep(&self, zpos: ScalarXyz, color: u8, len: usize) -> Warp {
             self[&color]
                 .slice(s![..len, ..])
                 .outer_iter()
@@ -443,166 +581,611 @@ pub mod prepare_yarn {
     }
 
     impl SegmentYarn for Warp {
-        fn chop(mut self: Warp, pins: &mut PinCushion) -> Warps {
-            match !pins.is_empty() {
-                true => self
-                    .par_iter()
-                    .enumerate()
-                    .filter_map(|(i, p)| pins.contains(p).then_some(i))
-                    .collect::<Vec<_>>()
-                    .into_iter()
-                    .enumerate()
-                    .rev()
-                    .flat_map(|(idx, i)| match idx == 0 {
-                        true if !pins.contains(&self[0]) => {
-                            vec![
-                                self.drain(i + 1..).collect::<Warp>(),
-                                self.drain(..).rev().collect::<Warp>(),
-                            ]
-                        }
-                        _ => vec![self.drain(i..).collect::<Warp>()],
-                    })
-                    .collect(),
-                false => {
-                    // No pins no cutting.
-                    self.reverse();
-                    vec![self]
-                }
+        fn chop(self: Warp, pins: &mut PinCushion) -> Warps {
+            // Hash the pins once so finding them along the yarn is linear in the size of the level.
+            let pinned = pins.iter().collect::<HashSet<_>>();
+            let positions = self
+                .par_iter()
+                .enumerate()
+                .filter_map(|(i, p)| pinned.contains(p).then_some(i))
+                .collect::<Vec<_>>();
+            chop_spans(&positions, self.len())
+                .into_iter()
+                .map(|YarnCut { span, .. }| (0..span.len()).map(|k| self[span.idx(k)]).collect())
+                .collect()
+        }
+    }
+
+    /// 🔪 A cut of the yarn of a level: the range of the yarn it covers read from its pin onwards, and the index of that pin among the pins. A cut without a pin starts a new thread.
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    pub struct YarnCut {
+        pub span: Span,
+        pub pin: Option<usize>,
+    }
+
+    /// 🔪 Where to cut the yarn of a level, `len` long, given the ascending positions of the pins on it. Each pin starts a cut running up to the next pin.
+    /// The yarn before the first pin is read backwards from that pin, leaving the rest of the first cut to start a new thread. Without pins the whole yarn, read backwards, is a new thread.
+    /// Both `SegmentYarn::chop` on vertices and `WeaveStrands` on strands cut the yarn here so the two looms are always built the same way.
+    pub fn chop_spans(pins: &[Count], len: Count) -> Vec<YarnCut> {
+        let ends = pins
+            .iter()
+            .skip(1)
+            .copied()
+            .chain([len])
+            .collect::<Vec<_>>();
+        let mut cuts = Vec::with_capacity(pins.len() + 1);
+        // From the last pin down, the order `chop` has always returned its cuts in.
+        for (p, (&start, &end)) in pins.iter().zip(&ends).enumerate().rev() {
+            match p == 0 && start > 0 {
+                true => cuts.extend([
+                    YarnCut {
+                        span: Span::new(start + 1, end),
+                        pin: None,
+                    },
+                    YarnCut {
+                        span: Span::new(0, start + 1).reversed(),
+                        pin: Some(p),
+                    },
+                ]),
+                false => cuts.push(YarnCut {
+                    span: Span::new(start, end),
+                    pin: Some(p),
+                }),
             }
         }
+        if pins.is_empty() {
+            cuts.push(YarnCut {
+                span: Span::new(0, len).reversed(),
+                pin: None,
+            });
+        }
+        cuts
     }
 }
 
 /// 🧮 Extend each thread in the loom based on the marked ends from the previous level.
 mod extend_threads {
     use crate::graph::types::*;
+    use std::collections::HashMap;
 
     /// Extend each end of each thread in the loom with the segmented, colored and finished yarn.
     pub trait ExtendLoomThreads {
         /// Extend each end of each thread in the loom with the segmented, colored and finished yarn.
+        /// Each cut starting with a pin is matched to the thread end holding that pin by looking it up in a map of the thread ends. Cuts that match no thread end become new threads.
         fn extend_threads(&mut self, warps: Warps);
     }
 
     impl ExtendLoomThreads for Loom {
-        fn extend_threads(&mut self, mut warps: Warps) {
-            self.iter_mut().for_each(|thread: &mut LoomThread| {
-                for warp in warps.iter_mut().filter(|w| !w.is_empty()) {
-                    match (thread[0] == warp[0], thread[thread.len() - 1] == warp[0]) {
-                        (true, _) => {
-                            thread.pop_front();
-                            warp.drain(..).for_each(|item| thread.push_front(item));
-                        }
-                        (_, true) => {
-                            thread.pop_back();
-                            thread.extend(warp.drain(..));
-                        }
-                        _ => continue,
+        fn extend_threads(&mut self, warps: Warps) {
+            // Thread end (the pin) -> index of the thread and whether it's the front.
+            let ends = self
+                .iter()
+                .enumerate()
+                .rev()
+                .flat_map(|(idx, thread)| {
+                    [
+                        (thread[thread.len() - 1], (idx, false)),
+                        (thread[0], (idx, true)),
+                    ]
+                })
+                .collect::<HashMap<_, _>>();
+            warps
+                .into_iter()
+                .filter(|warp| !warp.is_empty())
+                .for_each(|warp| match ends.get(&warp[0]) {
+                    Some(&(idx, true)) => {
+                        self[idx].pop_front();
+                        warp.into_iter().for_each(|item| self[idx].push_front(item));
                     }
-                }
-            });
-            warps.iter_mut().filter(|s| !s.is_empty()).for_each(|seq| {
-                self.push(seq.drain(..).collect::<LoomThread>());
-            });
+                    Some(&(idx, false)) => {
+                        self[idx].pop_back();
+                        self[idx].extend(warp);
+                    }
+                    None => self.push(warp.into_iter().collect::<LoomThread>()),
+                });
         }
     }
 }
 
 /// 🪞 Reflect the half-solution along the z-axis to create the whole: ◡ + ◠ = ◯.
+/// Only the lower half of each cycle is kept: the upper half is read off it, reflected, whenever it is iterated.
 mod mirror_loom {
-    use crate::graph::types::{Loom, Tour};
+    use super::splice::Span;
+    use crate::graph::types::*;
     use rayon::prelude::*;
 
+    /// A cycle kept as its lower half and a mirror flag. When mirrored the half is followed by its reflection `[x, y, -z]` read backwards.
+    /// The cycle is read in the order of its spans, which cover the half and its reflection as one sequence of twice the length of the half: rotating or reversing it only moves the spans.
+    #[derive(Clone, Debug, Default, PartialEq, Eq)]
+    pub struct HalfCycle {
+        /// The lower half of the cycle, the only vertices stored.
+        pub half: Warp,
+        /// Whether the half is followed by its reflection.
+        pub mirrored: bool,
+        /// The order in which the cycle is read.
+        pub spans: Vec<Span>,
+    }
+
+    impl HalfCycle {
+        /// The cycle made of the half, followed by its reflection if mirrored.
+        pub fn new(half: Warp, mirrored: bool) -> HalfCycle {
+            let len = half.len() * if mirrored { 2 } else { 1 };
+            HalfCycle {
+                half,
+                mirrored,
+                spans: vec![Span::new(0, len)],
+            }
+        }
+
+        /// Number of vertices in the cycle, reflected ones included.
+        pub fn len(&self) -> Count {
+            self.half.len() * if self.mirrored { 2 } else { 1 }
+        }
+
+        /// Whether the cycle has no vertices.
+        pub fn is_empty(&self) -> bool {
+            self.half.is_empty()
+        }
+
+        /// The vertex at `idx` in the half followed by its reflection, whatever the spans.
+        pub fn vert(&self, idx: Count) -> V3d {
+            match self.half.get(idx) {
+                Some(&vert) => vert,
+                None => {
+                    let [x, y, z] = self.half[self.len() - 1 - idx];
+                    [x, y, -z]
+                }
+            }
+        }
+
+        /// The vertices of the cycle in the order of its spans.
+        pub fn verts(&self) -> impl Iterator<Item = V3d> + '_ {
+            self.spans
+                .iter()
+                .flat_map(move |&span| (0..span.len()).map(move |k| self.vert(span.idx(k))))
+        }
+
+        /// The vertices of the cycle in the order of its spans, expanding the reflection into the storage of the half when the spans are untouched.
+        pub fn into_tour(self) -> Tour {
+            if self.spans != [Span::new(0, self.len())] {
+                return self.verts().collect();
+            }
+            let mut tour = self.half;
+            if self.mirrored {
+                tour.reserve_exact(tour.len());
+                (0..tour.len()).rev().for_each(|idx| {
+                    let [x, y, z] = tour[idx];
+                    tour.push([x, y, -z]);
+                });
+            }
+            tour
+        }
+    }
+
     /// For each thread in the loom all of whose ends are not adjacent, reflect each thread turning chains into cycles.
     /// Imagine reflecting a row of arcs to form a row of ovals.
     pub trait MirrorLoomThreads {
         /// For each thread in the loom all of whose ends are not adjacent, reflect each thread turning chains into cycles.
-        /// Imagine reflecting a row of arcs to form a row of ovals.
-        fn mirror_threads(&mut self);
+        /// Imagine reflecting a row of arcs to form a row of ovals. The reflection is not stored, only flagged.
+        fn mirror_threads(self) -> Vec<HalfCycle>;
     }
 
     impl MirrorLoomThreads for Loom {
-        fn mirror_threads(&mut self) {
-            self.par_iter_mut().for_each(|thread| {
-                thread.extend(
-                    thread
-                        .par_iter()
-                        .rev()
-                        .map(|&[x, y, z]| [x, y, -z])
-                        .collect::<Tour>(),
-                )
-            });
+        fn mirror_threads(self) -> Vec<HalfCycle> {
+            self.into_par_iter()
+                .map(|thread| HalfCycle::new(thread.into(), true))
+                .collect()
+        }
+    }
+}
+
+/// 🧷 Splice sequences by moving spans of them around instead of the items themselves. Rotating or reversing a sequence of spans costs as much as the number of spans, whatever their length.
+pub mod splice {
+    use crate::graph::types::*;
+
+    /// A range of a sequence read forwards or backwards.
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    pub struct Span {
+        /// Index of the first item of the range.
+        pub start: Count,
+        /// Index one past the last item of the range.
+        pub end: Count,
+        /// Whether the span is read from the end of the range to its start.
+        pub reversed: bool,
+    }
+
+    impl Span {
+        /// The range `start..end` read forwards.
+        pub fn new(start: Count, end: Count) -> Span {
+            Span {
+                start,
+                end,
+                reversed: false,
+            }
+        }
+
+        /// Number of items in the span.
+        pub fn len(&self) -> Count {
+            self.end - self.start
+        }
+
+        /// Whether the span has no items.
+        pub fn is_empty(&self) -> bool {
+            self.end == self.start
+        }
+
+        /// The same span read in the other direction.
+        pub fn reversed(self) -> Span {
+            Span {
+                reversed: !self.reversed,
+                ..self
+            }
+        }
+
+        /// Index in the sequence of the k-th item of the span.
+        pub fn idx(&self, k: Count) -> Count {
+            match self.reversed {
+                true => self.end - 1 - k,
+                false => self.start + k,
+            }
+        }
+
+        /// Offset within the span of the item at `idx` in the sequence, if the span covers it.
+        pub fn offset_of(&self, idx: Count) -> Option<Count> {
+            (self.start..self.end)
+                .contains(&idx)
+                .then(|| match self.reversed {
+                    true => self.end - 1 - idx,
+                    false => idx - self.start,
+                })
+        }
+
+        /// Split the span into its first k items and the rest.
+        pub fn split_at(self, k: Count) -> (Span, Span) {
+            match self.reversed {
+                true => (
+                    Span {
+                        start: self.end - k,
+                        ..self
+                    },
+                    Span {
+                        end: self.end - k,
+                        ..self
+                    },
+                ),
+                false => (
+                    Span {
+                        end: self.start + k,
+                        ..self
+                    },
+                    Span {
+                        start: self.start + k,
+                        ..self
+                    },
+                ),
+            }
+        }
+    }
+
+    /// Something that covers a span of some sequence.
+    pub trait Spanned: Copy {
+        /// The span covered.
+        fn span(&self) -> Span;
+        /// The same but covering another span.
+        fn with_span(self, span: Span) -> Self;
+    }
+
+    impl Spanned for Span {
+        fn span(&self) -> Span {
+            *self
+        }
+
+        fn with_span(self, span: Span) -> Span {
+            span
+        }
+    }
+
+    /// A span of one of the threads stored in the weft.
+    impl Spanned for ThreadSpan {
+        fn span(&self) -> Span {
+            self.1
+        }
+
+        fn with_span(self, span: Span) -> ThreadSpan {
+            (self.0, span)
+        }
+    }
+
+    /// A sequence made of spans, rotated and reversed by moving the spans.
+    pub trait Splice {
+        /// Number of items in the sequence.
+        fn order(&self) -> Count;
+        /// Index of the span holding the item at position k and the offset of k within it.
+        fn locate(&self, k: Count) -> Option<(usize, Count)>;
+        /// Rotate the sequence so that the item at position `mid` comes first.
+        fn rotate_at(&mut self, mid: Count);
+        /// Reverse the sequence.
+        fn reverse_spans(&mut self);
+    }
+
+    impl<S: Spanned> Splice for Vec<S> {
+        fn order(&self) -> Count {
+            self.iter().map(|s| s.span().len()).sum()
+        }
+
+        fn locate(&self, mut k: Count) -> Option<(usize, Count)> {
+            for (idx, s) in self.iter().enumerate() {
+                match k < s.span().len() {
+                    true => return Some((idx, k)),
+                    false => k -= s.span().len(),
+                }
+            }
+            None
+        }
+
+        fn rotate_at(&mut self, mid: Count) {
+            match self.locate(mid) {
+                Some((idx, 0)) => self.rotate_left(idx),
+                Some((idx, k)) => {
+                    let (head, tail) = self[idx].span().split_at(k);
+                    self[idx] = self[idx].with_span(head);
+                    self.insert(idx + 1, self[idx].with_span(tail));
+                    self.rotate_left(idx + 1);
+                }
+                None => {}
+            }
+        }
+
+        fn reverse_spans(&mut self) {
+            self.reverse();
+            self.iter_mut()
+                .for_each(|s| *s = s.with_span(s.span().reversed()));
         }
     }
 }
 
 /// 🪢 Merge subcycles into one Hamiltonian cycle by finding their bridges through set intersection.
 mod merge_cycles {
-    use super::{graph_info_from_n::InfoN, serialize_csv::SerializeToCsv};
-    use crate::graph::types::*;
+    use super::{
+        graph_info_from_n::InfoN,
+        mirror_loom::HalfCycle,
+        splice::{Span, Spanned, Splice},
+    };
+    use crate::graph::{
+        error::WeaveError,
+        sink::{CsvSink, TourSink},
+        types::*,
+    };
     use itertools::Itertools;
     use rayon::prelude::*;
+    use std::collections::HashMap;
 
-    /// Trait responsible for removing the subloops from the Loom and converting each thread from a VecDeque to a Vec and isolating the Weft and the main cycle into which Warps are incorporated.
+    /// Trait responsible for isolating the Weft, the main cycle into which Warps are incorporated, from the mirrored threads of the Loom.
     pub trait GetWeftWarps {
-        /// Remove the threads from the loom and separate into weft and warps. Prepare for cycle merging. Loop over each warp, join with the weft until the end of the loop where there will only be the weft.
-        fn prepare_cycle_merging(self, n: usize) -> (Weft, Warps);
-    }
-
-    impl GetWeftWarps for Loom {
-        fn prepare_cycle_merging(mut self, n: usize) -> (Weft, Warps) {
-            (
-                Weft::new(self[0].split_off(0), n.get_order_from_n()),
-                self.split_off(1)
-                    .into_par_iter()
-                    .map(|mut data| data.par_drain(..).collect())
-                    .collect(),
-            )
+        /// Separate the mirrored threads into weft and warps. Prepare for cycle merging. Loop over each warp, join with the weft until the end of the loop where there will only be the weft.
+        fn prepare_cycle_merging(self, n: usize) -> (Weft, Vec<HalfCycle>);
+    }
+
+    impl GetWeftWarps for Vec<HalfCycle> {
+        fn prepare_cycle_merging(mut self, n: usize) -> (Weft, Vec<HalfCycle>) {
+            let warps = self.split_off(1);
+            (Weft::new(self.swap_remove(0), n.get_order_from_n()), warps)
+        }
+    }
+
+    /// Trait responsible for cutting the weft at the bridges of all the warps at once, so that they are joined as a reduction tree instead of one by one.
+    pub trait GetWeftPieces {
+        /// Cut the weft at the bridge of each warp into pieces, each followed by its warp aligned to the bridge, and return them with a weft holding every thread but no spans yet.
+        /// The bridges are those `bridges()` computes for the warps in turn: each is an edge of the weft as mirrored, so the pieces don't depend on each other.
+        /// Joined in order, `Weft::reduce()`, the pieces read the same as the weft of `weave()`. Fails with `WeaveError::MisalignedWarp` if the ends of a bridge aren't next to each other.
+        fn prepare_cycle_reduction(
+            self,
+            n: usize,
+        ) -> Result<(Weft, Vec<Vec<ThreadSpan>>), WeaveError>;
+    }
+
+    impl GetWeftPieces for Vec<HalfCycle> {
+        fn prepare_cycle_reduction(
+            self,
+            n: usize,
+        ) -> Result<(Weft, Vec<Vec<ThreadSpan>>), WeaveError> {
+            let mut weft = Weft::empty(n.get_order_from_n());
+            let bridges = (1..self.len())
+                .map(|_| {
+                    let bridges = weft.bridges();
+                    weft.raise_bridge();
+                    bridges
+                })
+                .collect::<Vec<_>>();
+            weft.threads = self;
+            let len = weft.threads[0].len();
+            if bridges.is_empty() {
+                // A lone cycle is already the weft.
+                return Ok((weft, vec![vec![(0, Span::new(0, len))]]));
+            }
+            let ends = locate_in_half(
+                &weft.threads[0],
+                &bridges
+                    .iter()
+                    .flat_map(|&((lhs, rhs), _)| [lhs, rhs])
+                    .collect::<Vec<_>>(),
+            );
+            let next = |idx: Count| (idx + 1) % len;
+            // The weft is walked so that it crosses the bridge of the last warp from its rhs to its lhs, so the walk can start at that lhs as the weft of `weave()` does.
+            let forwards = match (ends[ends.len() - 2], ends[ends.len() - 1]) {
+                (Some(lhs), Some(rhs)) if next(rhs) == lhs => true,
+                (Some(lhs), Some(rhs)) if next(lhs) == rhs => false,
+                _ => return Err(WeaveError::MisalignedWarp),
+            };
+            let walk = |idx: Count| if forwards { idx } else { len - 1 - idx };
+            // Where the walk crosses the bridge of each warp and the warp read from the end next to where the walk comes from.
+            let mut cuts = bridges
+                .par_iter()
+                .enumerate()
+                .map(|(idx, &(_, (warp_lhs, warp_rhs)))| {
+                    let thread = idx + 1;
+                    let warp_ends = locate_in_half(&weft.threads[thread], &[warp_lhs, warp_rhs]);
+                    let (cut, path) = match (ends[2 * idx].map(walk), ends[2 * idx + 1].map(walk)) {
+                        (Some(lhs), Some(rhs)) if next(rhs) == lhs => {
+                            (rhs, (warp_ends[0], warp_ends[1]))
+                        }
+                        (Some(lhs), Some(rhs)) if next(lhs) == rhs => {
+                            (lhs, (warp_ends[1], warp_ends[0]))
+                        }
+                        _ => return Err(WeaveError::MisalignedWarp),
+                    };
+                    let mut warp = vec![(thread, Span::new(0, weft.threads[thread].len()))];
+                    align_path(&mut warp, path)?;
+                    Ok((cut, warp))
+                })
+                .collect::<Result<Vec<_>, WeaveError>>()?;
+            let start = cuts[cuts.len() - 1].0 + 1;
+            cuts.sort_unstable_by_key(|&(cut, _)| (cut + len - start) % len);
+            // The part of the weft walked from one bridge to the next, both ends included.
+            let arc = |from: Count, to: Count| {
+                match from <= to {
+                    true => vec![(from, to + 1)],
+                    false => vec![(from, len), (0, to + 1)],
+                }
+                .into_iter()
+                .map(move |(start, end)| match forwards {
+                    true => (0, Span::new(start, end)),
+                    false => (0, Span::new(len - end, len - start).reversed()),
+                })
+            };
+            let mut from = start % len;
+            let pieces = cuts
+                .into_iter()
+                .map(|(cut, warp)| {
+                    let piece = arc(from, cut).chain(warp).collect();
+                    from = next(cut);
+                    piece
+                })
+                .collect();
+            Ok((weft, pieces))
         }
     }
 
     /// Weft is the main loop into which warps are incorporated.
+    /// Warps are never moved once joined: the weft only rearranges spans of them and is flattened once by `get_woven()`.
+    /// Only the lower half of each is stored, the reflected vertices are read off it as the weft is iterated.
     #[derive(Clone, Debug)]
     pub struct Weft {
-        // Lead subcycle with which others are joined.
-        pub data: Tour,
+        // Lead subcycle and each warp joined with it, stored as they were when joined.
+        pub threads: Vec<HalfCycle>,
+        // The order in which the threads are read: each span is part of a thread, mirrored half included, read forwards or backwards.
+        pub spans: Vec<ThreadSpan>,
         // Indicates whether weft has joined which changes the conditions for valid bridge edges.
         pub joined: bool,
         // The maximum absolute scalar value for z.
-        pub max_abs_z: i16,
+        pub max_abs_z: ScalarXyz,
         // Max_abs_z * 2 to represent two z scalar values of an edge which is added together and then the absolute value compared to this value to determine current elevation.
-        pub max_sum_z: i16,
+        pub max_sum_z: ScalarXyz,
     }
 
     impl Weft {
         /// Create a new instance of weft which contains methods that are slightly different from those of warps.
         /// Weft is the main loop into which the warps are joined.
-        /// The final size of the solution is preallocated to the weft to avoid reallocations.
-        pub fn new(mut data: LoomThread, order: Count) -> Weft {
-            let mut preallocated = Warp::with_capacity(order);
-            preallocated.extend(data.drain(..));
-            let max_abs_z = (order.get_radius_from_order() - 4) as i16;
+        pub fn new(mut data: HalfCycle, order: Count) -> Weft {
+            let mut weft = Weft::empty(order);
+            weft.push(&mut data);
+            weft
+        }
+
+        /// Create a weft without data: only keeps track of where the next bridge is.
+        pub fn empty(order: Count) -> Weft {
+            let max_abs_z = order.get_radius_from_order() - 4;
             Weft {
-                data: preallocated,
+                threads: Vec::new(),
+                spans: Vec::new(),
                 joined: false,
                 max_abs_z,
                 max_sum_z: max_abs_z * 2,
             }
         }
 
+        /// Number of vertices in the weft.
+        pub fn order(&self) -> Count {
+            self.spans.order()
+        }
+
+        /// The vertices of the weft in order.
+        pub fn verts(&self) -> impl Iterator<Item = V3d> + '_ {
+            self.spans.iter().flat_map(move |&(thread, span)| {
+                (0..span.len()).map(move |k| self.threads[thread].vert(span.idx(k)))
+            })
+        }
+
+        /// The bridges joining the next warp: the edge of the weft and the parallel edge of the warp which replace each other.
+        /// Bridges lie at the predicted elevation and always at the same x and y so they are computed instead of searched for:
+        /// the first warp is bridged along x at the top of the weft, the others along z between the two levels around max_sum_z / 2.
+        pub fn bridges(&self) -> (WeftEdge, BridgeEdge) {
+            let z = self.max_sum_z / 2;
+            match self.joined {
+                false => (([1, 1, z], [3, 1, z]), ([3, 3, z], [1, 3, z])),
+                true => (
+                    ([1, 1, z - 1], [1, 1, z + 1]),
+                    ([3, 1, z + 1], [3, 1, z - 1]),
+                ),
+            }
+        }
+
+        /// Find the bridges by intersecting the edges of the weft and the warp, which is what `bridges()` computes.
+        /// Only compiled in debug builds and tests, where it cross-checks `bridges()`.
+        #[cfg(any(debug_assertions, test))]
+        pub fn search_bridges(
+            &self,
+            warp: &HalfCycle,
+        ) -> Result<(WeftEdge, BridgeEdge), WeaveError> {
+            let warp_edges = warp.edges(self.joined);
+            let weft_bridge =
+                self.edges()?
+                    .bridge(&warp_edges)
+                    .map_err(|_| WeaveError::MissingBridge {
+                        z: self.max_sum_z / 2,
+                    })?;
+            Ok((weft_bridge, warp_edges.bridge(&weft_bridge)?))
+        }
+
         /// Convert data into set of edges using `tuple_windows()` and filter using condition below resulting at most two edges.
-        /// We are looking for only one edge so we use find to stop iterating over self.data once that one edge has been found.
-        pub fn edges(&mut self) -> Edges {
-            self.data
-                .iter()
+        /// We are looking for only one edge so we use find to stop iterating over the weft once that one edge has been found.
+        /// If no edge is found at the predicted elevation there is no bridge to join the next warp to.
+        #[cfg(any(debug_assertions, test))]
+        pub fn edges(&self) -> Result<Edges, WeaveError> {
+            self.verts()
                 .tuple_windows()
                 // find just one edge matching condition use find instead of iterating over entire tupled windows.
-                .find(|(&[x, _, z], &[a, _, c])| {
+                .find(|&([x, _, z], [a, _, c])| {
                     [x, a] == if self.joined { [1, 1] } else { [1, 3] }
                         // Matching to the current merge elevation which always increases. 
                         && (z + c).abs() == self.max_sum_z
                 })
-                .map(|(m, n)| Edges::from([(*m, *n).orient()]))
-                .unwrap()
+                .map(|(m, n)| Edges::from([(m, n).orient()]))
+                .ok_or(WeaveError::MissingBridge {
+                    z: self.max_sum_z / 2,
+                })
+        }
+
+        /// Same as `AlignToEdge` but moves spans instead of vertices.
+        /// The bridge lies near the start of the weft so the weft is searched for both ends at once, stopping at the bridge.
+        pub fn align_to(&mut self, (lhs, rhs): WeftEdge) -> Result<(), WeaveError> {
+            let adjacent =
+                self.verts()
+                    .tuple_windows()
+                    .enumerate()
+                    .find_map(|(idx, (m, n))| match (m, n) {
+                        edge if edge == (lhs, rhs) => Some((Some(idx), Some(idx + 1))),
+                        edge if edge == (rhs, lhs) => Some((Some(idx + 1), Some(idx))),
+                        _ => None,
+                    });
+            // Otherwise the bridge is the edge closing the cycle, or isn't in the weft.
+            let positions = adjacent.unwrap_or_else(|| {
+                (
+                    self.verts().position(|x| x == lhs),
+                    self.verts().position(|x| x == rhs),
+                )
+            });
+            align_spans(&mut self.spans, positions)
         }
 
         /// Join the warp with the weft. The bridges change once the warp has been joined once.
@@ -610,8 +1193,33 @@ mod merge_cycles {
         /// Once joined a different set of edges to used as bridges is valid.
         /// As cycles are joined level by level we can calculate the predicted location/elevation of the bridge edge
         /// using self.max_sum_z which is incremented each time a sequence is joined.
-        pub fn join(&mut self, warp: &mut Warp) {
-            self.data.append(warp);
+        pub fn join(&mut self, warp: &mut HalfCycle) {
+            self.push(warp);
+            self.raise_bridge();
+        }
+
+        /// Join the pieces of `prepare_cycle_reduction()` pairwise, each with its neighbour, all pairs in parallel, round after round until one is left, which is read as the weft.
+        /// Only the spans move, so a round costs as much as the number of spans, whatever the length of the threads.
+        pub fn reduce(&mut self, mut pieces: Vec<Vec<ThreadSpan>>) {
+            while pieces.len() > 1 {
+                pieces = pieces
+                    .par_chunks_mut(2)
+                    .map(|pair| pair.iter_mut().flat_map(std::mem::take).collect())
+                    .collect();
+            }
+            self.spans = pieces.pop().unwrap_or_default();
+        }
+
+        /// Append the spans of the cycle to the end of the weft and keep its half.
+        fn push(&mut self, cycle: &mut HalfCycle) {
+            let thread = self.threads.len();
+            self.spans
+                .extend(cycle.spans.iter().map(|&span| (thread, span)));
+            self.threads.push(std::mem::take(cycle));
+        }
+
+        /// Move the predicted elevation of the bridge to where the next warp joins.
+        pub fn raise_bridge(&mut self) {
             match self.joined {
                 true => self.max_abs_z -= 4,
                 false => {
@@ -624,30 +1232,36 @@ mod merge_cycles {
 
         /// Retrieve the finished solution.
         pub fn get_woven(&self) -> Solution {
-            self.data.to_vec()
+            let mut woven = Solution::with_capacity(self.order());
+            woven.extend(self.verts());
+            woven
         }
 
         /// Save the finished solution to a csv file.
         pub fn export_csv(&self, filepath: &str) -> Result<(), Box<dyn std::error::Error>> {
-            self.data.serialize_to_csv(filepath)?;
+            let mut sink = CsvSink::create(filepath)?;
+            self.verts().try_for_each(|vert| sink.put(vert))?;
+            sink.finish()?;
             Ok(())
         }
     }
 
     /// Get a tuple_window of self but filtered so that only the required edges are pass through the filter.
+    #[cfg(any(debug_assertions, test))]
     pub trait GetWarpEdges {
         /// Construct edges from the Vec and filter.
         /// 'vec![1, 2, 3]' -> `hash_set![(1, 2), (2, 3), (3, 1)]` which is then filtered further to avoid memory waste.
         fn edges(&self, joined: bool) -> Edges;
     }
 
-    impl GetWarpEdges for Warp {
+    #[cfg(any(debug_assertions, test))]
+    impl GetWarpEdges for HalfCycle {
         fn edges(&self, joined: bool) -> Edges {
             // The needed edge is located after the first third so let's start there.
-            self[(self.len() / 3)..]
-                .iter()
+            self.verts()
+                .skip(self.len() / 3)
                 .tuple_windows()
-                .filter_map(|(&[x, y, z], &[a, b, c])| {
+                .filter_map(|([x, y, z], [a, b, c])| {
                     ([b, x, y] == if joined { [1, 3, 1] } else { [3, 1, 3] })
                         .then_some(([x, y, z], [a, b, c]).orient())
                 })
@@ -671,67 +1285,81 @@ mod merge_cycles {
     }
 
     /// Get the bridge edge between self and other.
+    #[cfg(any(debug_assertions, test))]
     pub trait Bridge<T> {
         /// Using the & set operator, find the common bridge i.e., intersection between a set of edges and a set of adjacent edges and return the next() from the set.
-        fn bridge(&self, other: &T) -> Edge;
+        fn bridge(&self, other: &T) -> Result<Edge, WeaveError>;
     }
 
+    #[cfg(any(debug_assertions, test))]
     impl Bridge<WeftEdge> for WarpEdges {
         /// Using the & set operator, find the common bridge i.e., intersection between a set of edges and a set of adjacent edges and return the next() from the set.
-        fn bridge(&self, (weft_lhs, weft_rhs): &WeftEdge) -> BridgeEdge {
-            let &(warp_lhs, warp_rhs) = ((*weft_lhs, *weft_rhs).eadjs())
+        fn bridge(&self, (weft_lhs, weft_rhs): &WeftEdge) -> Result<BridgeEdge, WeaveError> {
+            let &(warp_lhs, warp_rhs) = ((*weft_lhs, *weft_rhs).eadjs()?)
                 .intersection(self)
                 .next()
-                .unwrap();
-            (warp_rhs, warp_lhs)
+                .ok_or(WeaveError::MissingBridge { z: weft_lhs[2] })?;
+            Ok((warp_rhs, warp_lhs))
         }
     }
 
+    #[cfg(any(debug_assertions, test))]
     impl Bridge<WarpEdges> for WeftEdges {
-        fn bridge(&self, other: &WarpEdges) -> BridgeEdge {
-            (self & &other.eadjs()).into_iter().next().unwrap()
+        fn bridge(&self, other: &WarpEdges) -> Result<BridgeEdge, WeaveError> {
+            (self & &other.eadjs()?)
+                .into_iter()
+                .next()
+                // The weft edges all lie at the predicted elevation of `Weft::edges`, half their summed z.
+                .ok_or(WeaveError::MissingBridge {
+                    z: self
+                        .iter()
+                        .next()
+                        .map_or(0, |(lhs, rhs)| (lhs[2] + rhs[2]).abs() / 2),
+                })
         }
     }
 
     /// Get the adjacent/parallel edge of self either for WarpEdges or for WeftEdge
+    #[cfg(any(debug_assertions, test))]
     pub trait GetEadjs {
         /// Get the adjacent/parallel edges of edges.
-        fn eadjs(&self) -> Edges;
+        fn eadjs(&self) -> Result<Edges, WeaveError>;
     }
 
+    #[cfg(any(debug_assertions, test))]
     impl GetEadjs for WarpEdges {
-        fn eadjs(&self) -> Edges {
+        fn eadjs(&self) -> Result<Edges, WeaveError> {
             self.par_iter()
-                .filter_map(|&([a, b, c], [x, y, z])| {
-                    ((x == 3 || x == 1) && (y == 3 || y == 1)).then_some(
-                        // Determine along which axis this edge lies to get parallel edge.
-                        match (a != x, b != y, c != z) {
-                            // Edge along x-axis
-                            (true, false, false) => ([a, b - 2, c], [x, y - 2, z]),
-                            // Edge along y-axis
-                            (false, true, false) => ([a, b, c + 2], [x, y, z + 2]),
-                            // Edge along z-axis
-                            (false, false, true) => ([a - 2, b, c], [x - 2, y, z]),
-                            _ => panic!("NOT A VALID EDGE"),
-                        },
-                    )
+                .filter(|(_, [x, y, _])| (*x == 3 || *x == 1) && (*y == 3 || *y == 1))
+                .map(|&([a, b, c], [x, y, z])| {
+                    // Determine along which axis this edge lies to get parallel edge.
+                    match (a != x, b != y, c != z) {
+                        // Edge along x-axis
+                        (true, false, false) => Ok(([a, b - 2, c], [x, y - 2, z])),
+                        // Edge along y-axis
+                        (false, true, false) => Ok(([a, b, c + 2], [x, y, z + 2])),
+                        // Edge along z-axis
+                        (false, false, true) => Ok(([a - 2, b, c], [x - 2, y, z])),
+                        _ => Err(WeaveError::InvalidEdge(([a, b, c], [x, y, z]))),
+                    }
                 })
                 .collect()
         }
     }
 
+    #[cfg(any(debug_assertions, test))]
     impl GetEadjs for WeftEdge {
-        fn eadjs(&self) -> Edges {
+        fn eadjs(&self) -> Result<Edges, WeaveError> {
             let ([a, b, c], [x, y, z]) = *self;
             // Determine along which axis this edge lies to get parallel edge.
             match (a != x, b != y, c != z) {
                 // Edge along x-axis
-                (true, false, false) => [([a, b + 2, c], [x, y + 2, z])].into(),
+                (true, false, false) => Ok([([a, b + 2, c], [x, y + 2, z])].into()),
                 // Edge along y-axis
-                (false, true, false) => [([a + 2, b, c], [x + 2, y, z])].into(),
+                (false, true, false) => Ok([([a + 2, b, c], [x + 2, y, z])].into()),
                 // Edge along z-axis
-                (false, false, true) => [([a + 2, b, c], [x + 2, y, z])].into(),
-                _ => panic!("NOT A VALID EDGE"),
+                (false, false, true) => Ok([([a + 2, b, c], [x + 2, y, z])].into()),
+                _ => Err(WeaveError::InvalidEdge(*self)),
             }
         }
     }
@@ -740,21 +1368,109 @@ mod merge_cycles {
     pub trait AlignToEdge<T: PartialEq + Copy> {
         /// Align self to given edge such that the lhs of edge and self match and the rhs of edge and self match.
         /// Only the cases that occur in the code are covered. It is not to be used generally as not all cases are covered.
-        fn align_to(&mut self, edge: (T, T));
+        /// Fails if either end of the edge is not in self.
+        fn align_to(&mut self, edge: (T, T)) -> Result<(), WeaveError>;
+    }
+
+    /// Rotate the spans so the rhs comes first then reverse them if the lhs comes before the rhs, otherwise rotate them so the lhs comes first.
+    fn align_spans<S: Spanned>(
+        spans: &mut Vec<S>,
+        positions: (Option<Count>, Option<Count>),
+    ) -> Result<(), WeaveError> {
+        match positions {
+            (Some(idx_lhs), Some(idx_rhs)) if idx_lhs < idx_rhs => {
+                spans.rotate_at(idx_rhs);
+                spans.reverse_spans()
+            }
+            (Some(idx_lhs), Some(_)) => spans.rotate_at(idx_lhs),
+            _ => return Err(WeaveError::MisalignedWarp),
+        }
+        Ok(())
+    }
+
+    /// Rotate and reverse the spans so they're read from the first end to the last, going away from it: the two are next to each other and the edge between them closes the cycle.
+    /// Unlike `align_spans()` the ends may lie at both ends of the spans.
+    fn align_path<S: Spanned>(
+        spans: &mut Vec<S>,
+        ends: (Option<Count>, Option<Count>),
+    ) -> Result<(), WeaveError> {
+        let order = spans.order();
+        match ends {
+            (Some(first), Some(last)) if (first + 1) % order == last => {
+                spans.rotate_at(last);
+                spans.reverse_spans()
+            }
+            (Some(first), Some(last)) if (last + 1) % order == first => spans.rotate_at(first),
+            _ => return Err(WeaveError::MisalignedWarp),
+        }
+        Ok(())
+    }
+
+    /// Index of each of the vertices in the half of the cycle followed by its reflection, whatever its spans, found in one pass over the half.
+    fn locate_in_half(cycle: &HalfCycle, verts: &[V3d]) -> Vec<Option<Count>> {
+        let slots = verts
+            .iter()
+            .enumerate()
+            .map(|(slot, &vert)| (vert, slot))
+            .collect::<HashMap<_, _>>();
+        // The vertices are few and close together, so most of the half is passed over on x and y without hashing, the reflection included.
+        let within = |axis: usize| {
+            verts
+                .iter()
+                .map(|vert| vert[axis])
+                .minmax()
+                .into_option()
+                .map(|(min, max)| min..=max)
+        };
+        let (xs, ys) = (within(0), within(1));
+        let len = cycle.len();
+        let mut found = vec![None; verts.len()];
+        cycle
+            .half
+            .par_iter()
+            .enumerate()
+            .filter(|(_, [x, y, _])| {
+                xs.as_ref().is_some_and(|xs| xs.contains(x))
+                    && ys.as_ref().is_some_and(|ys| ys.contains(y))
+            })
+            .flat_map_iter(|(idx, &[x, y, z])| {
+                let reflected = cycle.mirrored.then_some(([x, y, -z], len - 1 - idx));
+                [([x, y, z], idx)]
+                    .into_iter()
+                    .chain(reflected)
+                    .filter_map(|(vert, idx)| Some((*slots.get(&vert)?, idx)))
+            })
+            .collect::<Vec<_>>()
+            .into_iter()
+            .for_each(|(slot, idx)| found[slot] = Some(idx));
+        found
+    }
+
+    /// Same as for a `Vec` but moves the spans of the cycle, so the half is never copied nor its reflection stored.
+    impl AlignToEdge<V3d> for HalfCycle {
+        fn align_to(&mut self, (lhs, rhs): Edge) -> Result<(), WeaveError> {
+            let positions = (
+                self.verts().position(|x| x == lhs),
+                self.verts().position(|x| x == rhs),
+            );
+            align_spans(&mut self.spans, positions)
+        }
     }
 
     impl<T: PartialEq + Copy> AlignToEdge<T> for Vec<T> {
-        fn align_to(&mut self, (lhs, rhs): (T, T)) {
+        fn align_to(&mut self, (lhs, rhs): (T, T)) -> Result<(), WeaveError> {
             match (
-                self.iter().position(|&x| x == lhs).unwrap(),
-                self.iter().position(|&x| x == rhs).unwrap(),
+                self.iter().position(|&x| x == lhs),
+                self.iter().position(|&x| x == rhs),
             ) {
-                (idx_lhs, idx_rhs) if idx_lhs < idx_rhs => {
+                (Some(idx_lhs), Some(idx_rhs)) if idx_lhs < idx_rhs => {
                     self.rotate_left(idx_rhs);
                     self.reverse()
                 }
-                (idx_lhs, _) => self.rotate_left(idx_lhs),
+                (Some(idx_lhs), Some(_)) => self.rotate_left(idx_lhs),
+                _ => return Err(WeaveError::MisalignedWarp),
             }
+            Ok(())
         }
     }
 }
@@ -763,9 +1479,12 @@ mod merge_cycles {
 pub mod grade_solution {
     use itertools::Itertools;
 
-    use super::prelude::{Edge, Solution};
+    use super::{
+        ham_cycle::HamCycle,
+        prelude::{Edge, V3d},
+    };
 
-    /// Grade Solution for edges' axes count and count of nonturns in solution.
+    /// Grade a Hamiltonian cycle for edges' axes count and count of nonturns in solution.
     pub trait Grade {
         /// Calculate the axis on which each edge lies.
         fn axes(&self) -> [usize; 3];
@@ -775,12 +1494,12 @@ pub mod grade_solution {
         fn nonturns(&self) -> usize;
     }
 
-    impl Grade for Solution {
+    impl Grade for HamCycle {
         fn axes(&self) -> [usize; 3] {
             self.iter()
                 .circular_tuple_windows()
                 .fold([0; 3], |mut acc, (m, n)| {
-                    acc[(*m, *n).axis()] += 1;
+                    acc[cycle_axis(*m, *n)] += 1;
                     acc
                 })
         }
@@ -798,11 +1517,11 @@ pub mod grade_solution {
 
         fn nonturns(&self) -> usize {
             let mut nonturns: usize = 0;
-            let mut prev_axis = (self[self.len() - 1], self[0]).axis();
+            let mut prev_axis = cycle_axis(self[self.len() - 1], self[0]);
             self.iter()
                 .circular_tuple_windows()
-                .for_each(|(m, n): (&[i16; 3], &[i16; 3])| {
-                    let curr_axis = match (*m, *n).axis() {
+                .for_each(|(m, n): (&V3d, &V3d)| {
+                    let curr_axis = match cycle_axis(*m, *n) {
                         curr_axis if curr_axis == prev_axis => {
                             nonturns += 1;
                             curr_axis
@@ -815,20 +1534,27 @@ pub mod grade_solution {
         }
     }
 
+    /// The axis of an edge of a Hamiltonian cycle: consecutive vertices always differ along one axis by 2.
+    fn cycle_axis(lhs: V3d, rhs: V3d) -> usize {
+        (lhs, rhs)
+            .axis()
+            .expect("consecutive vertices of a Hamiltonian cycle are an edge")
+    }
+
     /// Get the given axis of a self and test if the length is the edge's unit length of 2.
     pub trait GetEdgeAxis {
-        /// Get axis of edge.
-        fn axis(&self) -> usize;
+        /// Get axis of edge, None if the pair isn't an edge: the same vertex twice, or vertices apart along more than one axis or by other than 2.
+        fn axis(&self) -> Option<usize>;
     }
 
     impl GetEdgeAxis for Edge {
-        fn axis(&self) -> usize {
+        fn axis(&self) -> Option<usize> {
             let ([a, b, c], [x, y, z]) = *self;
-            match [a != x, b != y, c != z] {
-                [true, false, false] if ((a - x).abs() == 2) => 0,
-                [false, true, false] if ((b - y).abs() == 2) => 1,
-                [false, false, true] if ((c - z).abs() == 2) => 2,
-                _ => panic!("NOT A VALID EDGE"),
+            match [a.abs_diff(x), b.abs_diff(y), c.abs_diff(z)] {
+                [2, 0, 0] => Some(0),
+                [0, 2, 0] => Some(1),
+                [0, 0, 2] => Some(2),
+                _ => None,
             }
         }
     }
@@ -846,21 +1572,21 @@ pub mod grade_solution {
             // Test cube (the first instance) which has a different count ratio than the proceeding orders.
             let n_1 = 1_usize;
             let mut order = n_1.get_order_from_n();
-            let [mut x_count, mut y_count, mut z_count] = weave(n_1).axes();
+            let [mut x_count, mut y_count, mut z_count] = weave(n_1).unwrap().axes();
             // Test that x and z count is 2 and y count == 4.
             assert!(z_count == x_count && x_count == 2 && y_count == 4);
             // sum of the xyz counts should equal the order.
-            assert_eq!(order, [x_count, y_count, z_count].iter().sum());
+            assert_eq!(order, [x_count, y_count, z_count].iter().sum::<usize>());
             // Test the rest of the orders which have the same count proportions.
             for n in 2..=100 {
                 order = n.get_order_from_n();
-                let solution = weave(n);
+                let solution = weave(n).unwrap();
                 [x_count, y_count, z_count] = solution.axes();
                 let [x_part, y_part, z_part] = solution.axis_percent();
                 // check that z < x < y;
                 assert!(z_count < x_count && x_count < y_count);
                 // sum of the xyz counts should equal the order.
-                assert_eq!(order, [x_count, y_count, z_count].iter().sum());
+                assert_eq!(order, [x_count, y_count, z_count].iter().sum::<usize>());
                 // check that x and y are around about 50% and z at 1% when rounded. Closer to 50/50/1 when n increases.
                 let [xc, yc, zc] = [x_part.round(), y_part.round(), z_part.round()];
                 assert!(if n > 48 && n < 70 {
@@ -877,42 +1603,45 @@ pub mod grade_solution {
         /// Solve for n and count nonturns if n < 3 the result is zero else 2.
         fn test_count_nonturns() {
             for n in 1..=100 {
-                assert_eq!(if n < 3 { 0 } else { 2 }, weave(n).nonturns());
+                assert_eq!(if n < 3 { 0 } else { 2 }, weave(n).unwrap().nonturns());
             }
         }
 
         #[test]
         /// Test if given edge.axis() is expected.
         fn test_edge_axis() {
-            assert_eq!(([0, 2, 0], [0, 0, 0]).axis(), 1);
-            assert_eq!(([0, 2, 0], [0, 2, 2]).axis(), 2);
-            assert_eq!(([0, 2, 2], [2, 2, 2]).axis(), 0);
+            assert_eq!(([0, 2, 0], [0, 0, 0]).axis(), Some(1));
+            assert_eq!(([0, 2, 0], [0, 2, 2]).axis(), Some(2));
+            assert_eq!(([0, 2, 2], [2, 2, 2]).axis(), Some(0));
         }
 
         #[test]
-        #[should_panic(expected = "NOT A VALID EDGE")]
-        /// Test expected panic of same edge.
+        /// Test that the same vertex twice has no axis.
         fn test_invalid_same_edge() {
-            ([0, 2, 0], [0, 2, 0]).axis();
+            assert_eq!(([0, 2, 0], [0, 2, 0]).axis(), None);
         }
 
         #[test]
-        #[should_panic(expected = "NOT A VALID EDGE")]
-        /// Test expected panic of invalid Edge Length.
+        /// Test that vertices apart by other than 2, or along two axes, have no axis.
         fn test_invalid_same_edge_bad_length() {
-            ([0, 2, 0], [0, 2, 3]).axis();
+            assert_eq!(([0, 2, 0], [0, 2, 3]).axis(), None);
+            assert_eq!(([0, 2, 0], [2, 0, 0]).axis(), None);
         }
     }
 }
 
 /// ✅ Certify if the solution is Hamiltonian.
 pub mod certify_solution {
-    use crate::graph::types::{Solution, V2d, V3d};
-    use itertools::{all, Itertools};
+    use super::translate::unrank_unbounded;
+    use crate::graph::types::{Node, ScalarXyz, V2d, V3d};
 
-    use std::fmt;
+    use std::{
+        collections::{HashMap, HashSet},
+        fmt,
+        hash::Hash,
+    };
 
-    #[derive(Debug, PartialEq)]
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
     /// Enum describing possible ids of a solution: Broken, Chain, Cycle.
     pub enum SequenceID {
         Broken,
@@ -931,181 +1660,316 @@ pub mod certify_solution {
         }
     }
 
-    /// Certify if a sequence is a Hamiltonian cycle by:
+    /// What each check of the certification found, so a broken sequence says why it is broken.
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    pub struct CertifyReport<V = V3d> {
+        /// The resulting id of the sequence.
+        pub id: SequenceID,
+        /// Length of the sequence.
+        pub len: usize,
+        /// Order of the graph, which the length must match.
+        pub order: usize,
+        /// Each vertex found more than once with all of its positions, in order of first position.
+        pub duplicates: Vec<(V, Vec<usize>)>,
+        /// Each vertex outside of the graph, e.g. with an L1-norm above max_absumv3d, with its position.
+        pub out_of_bounds: Vec<(usize, V)>,
+        /// The first pair of positions of consecutive vertices which are not adjacent.
+        pub not_adjacent: Option<(usize, usize)>,
+        /// Whether the last vertex is adjacent to the first.
+        pub closed: bool,
+        /// The sum of all the vertices, their displacement vectors from the origin, which is [0, 0, 0] for the whole discocube and for graphs without coordinates.
+        pub displacement_sum: [i64; 3],
+    }
+
+    /// impl Display to print the id followed by each failed check.
+    impl<V: fmt::Debug> fmt::Display for CertifyReport<V> {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "{}", self.id)?;
+            if self.len != self.order {
+                write!(f, " | length {} != order {}", self.len, self.order)?;
+            }
+            for (vert, positions) in self.duplicates.iter().take(3) {
+                write!(f, " | duplicate {vert:?} at {positions:?}")?;
+            }
+            for (position, vert) in self.out_of_bounds.iter().take(3) {
+                write!(f, " | out of bounds {vert:?} at {position}")?;
+            }
+            if let Some((lhs, rhs)) = self.not_adjacent {
+                write!(f, " | not adjacent at {lhs} and {rhs}")?;
+            }
+            if self.displacement_sum != [0, 0, 0] {
+                write!(f, " | displacement sum {:?}", self.displacement_sum)?;
+            }
+            Ok(())
+        }
+    }
+
+    /// Certify if a sequence is a Hamiltonian cycle of a graph by:
     /// Checking for duplicates
     /// Check that solution length is equal to the order of the graph.
-    /// The sum of all displacement vectors used to construct the cycle is equal to [0, 0, 0]
+    /// Check that each vertex is in the graph.
+    /// The sum of all displacement vectors used to construct the cycle is equal to [0, 0, 0], where the graph has coordinates.
     /// Check that the current node is adjacent to the next node.
     /// Check that the last node is adjacent to the first. If not is is a HamChain else HamCycle.
-    pub trait Certify<SequenceID> {
-        /// Certify if a sequence is a Hamiltonian cycle by:
-        /// Checking for duplicates
-        /// Check that solution length is equal to the order of the graph.
-        /// The sum of all displacement vectors used to construct the cycle is equal to [0, 0, 0]
-        /// Check that the current node is adjacent to the next node.
-        /// Check that the last node is adjacent to the first. If not is is a HamChain else HamCycle.
-        fn certify(&self, order: usize, max_absumv3d: i16) -> SequenceID;
-    }
-
-    impl Certify<SequenceID> for Solution {
-        fn certify(&self, order: usize, max_absumv3d: i16) -> SequenceID {
-            if self.iter().duplicates().count() > 0
-                || self.len() != order
-                || !all(self.iter(), |vert| vert.l1norm() <= max_absumv3d)
-                || self
-                    .iter()
-                    .fold((0, 0, 0), |acc: (i64, i64, i64), &[x, y, z]| {
-                        (acc.0 + x as i64, acc.1 + y as i64, acc.2 + z as i64)
-                    })
-                    != (0, 0, 0)
-            {
-                return SequenceID::Broken;
-            }
-            match self.windows(2).all(|window| window[0].is_adj_to(window[1])) {
-                true if self[self.len() - 1].is_adj_to(self[0]) => SequenceID::HamCycle,
-                true => SequenceID::HamChain,
-                false => SequenceID::Broken,
-            }
+    ///
+    /// Works over any vertex type V and any graph given as an `Adjacency` oracle: the implicit `Discocube`, a `HashMap<V, HashSet<V>>` or a `Csr`.
+    pub trait Certify<V> {
+        /// Same checks as `certify_in` but reporting what each check found.
+        fn certify_report_in<G: Adjacency<V>>(&self, graph: &G) -> CertifyReport<V>;
+
+        /// Certify the sequence as a tour of graph. Same id as `certify_report_in`, but stops at the first failed check.
+        fn certify_in<G: Adjacency<V>>(&self, graph: &G) -> SequenceID {
+            self.certify_report_in(graph).id
         }
-    }
 
-    /// Calculate the L1-norm for a vector, i.e., the sum of the absolute value of each scalar x, y and or z.
-    /// Also known as the L1-Norm- manhattan distance from a to b where b is the origin (0, 0, 0)
-    /// The L1 norm is calculated as the sum of the absolute vector values, where the absolute value of a scalar uses the notation |a1|.
-    /// In effect, the norm is a calculation of the Manhattan distance from the origin of the vector space.
-    pub trait L1Norm {
-        /// Calculate the L1-norm for a vector, i.e., the sum of the absolute value of each scalar x, y and or z.
-        fn l1norm(&self) -> i16;
+        /// Certify the sequence as a tour of the discocube with `order` vertices whose L1-norms are at most `max_absumv3d`.
+        fn certify(&self, order: usize, max_absumv3d: ScalarXyz) -> SequenceID
+        where
+            Discocube: Adjacency<V>,
+        {
+            self.certify_in(&Discocube::new(order, max_absumv3d))
+        }
+
+        /// Same checks as `certify` but reporting what each check found.
+        fn certify_report(&self, order: usize, max_absumv3d: ScalarXyz) -> CertifyReport<V>
+        where
+            Discocube: Adjacency<V>,
+        {
+            self.certify_report_in(&Discocube::new(order, max_absumv3d))
+        }
     }
 
-    impl L1Norm for V3d {
-        fn l1norm(&self) -> i16 {
+    impl<V: Copy + Eq + Hash> Certify<V> for [V] {
+        fn certify_report_in<G: Adjacency<V>>(&self, graph: &G) -> CertifyReport<V> {
+            // Only the positions of revisits are kept, so a tour without duplicates holds one set of its vertices.
+            let mut visited = HashSet::with_capacity(self.len());
+            let mut revisits: HashMap<V, Vec<usize>> = HashMap::new();
             self.iter()
-                .map(|v| {
-                    let mask = v >> 15;
-                    (v ^ mask) - mask
-                })
-                .sum()
+                .enumerate()
+                .filter(|(_, vert)| !visited.insert(**vert))
+                .for_each(|(idx, &vert)| revisits.entry(vert).or_default().push(idx));
+            drop(visited);
+            // The first visit of each duplicate is found in order, which orders them by first position.
+            let mut duplicates = Vec::with_capacity(revisits.len());
+            for (idx, vert) in self.iter().enumerate() {
+                if revisits.is_empty() {
+                    break;
+                }
+                if let Some(positions) = revisits.remove(vert) {
+                    duplicates.push((*vert, [vec![idx], positions].concat()));
+                }
+            }
+            let out_of_bounds = self
+                .iter()
+                .enumerate()
+                .filter(|(_, vert)| !graph.contains(vert))
+                .map(|(idx, &vert)| (idx, vert))
+                .collect::<Vec<_>>();
+            let not_adjacent = self
+                .windows(2)
+                .position(|window| !graph.is_adjacent(&window[0], &window[1]))
+                .map(|idx| (idx, idx + 1));
+            let closed = self.len() > 1 && graph.is_adjacent(&self[self.len() - 1], &self[0]);
+            let displacement_sum = self.iter().fold([0; 3], |acc: [i64; 3], vert| {
+                let [x, y, z] = graph.displacement(vert);
+                [acc[0] + x, acc[1] + y, acc[2] + z]
+            });
+            let id = match duplicates.is_empty()
+                && self.len() == graph.order()
+                && out_of_bounds.is_empty()
+                && displacement_sum == [0, 0, 0]
+                && not_adjacent.is_none()
+            {
+                true if closed => SequenceID::HamCycle,
+                true => SequenceID::HamChain,
+                false => SequenceID::Broken,
+            };
+            CertifyReport {
+                id,
+                len: self.len(),
+                order: graph.order(),
+                duplicates,
+                out_of_bounds,
+                not_adjacent,
+                closed,
+                displacement_sum,
+            }
+        }
+
+        fn certify_in<G: Adjacency<V>>(&self, graph: &G) -> SequenceID {
+            if self.len() != graph.order() {
+                return SequenceID::Broken;
+            }
+            let mut visited = HashSet::with_capacity(self.len());
+            let mut displacement_sum = [0; 3];
+            for (idx, vert) in self.iter().enumerate() {
+                if !visited.insert(*vert)
+                    || !graph.contains(vert)
+                    || (idx > 0 && !graph.is_adjacent(&self[idx - 1], vert))
+                {
+                    return SequenceID::Broken;
+                }
+                let [x, y, z] = graph.displacement(vert);
+                displacement_sum = [
+                    displacement_sum[0] + x,
+                    displacement_sum[1] + y,
+                    displacement_sum[2] + z,
+                ];
+            }
+            match displacement_sum == [0, 0, 0] {
+                true if self.len() > 1 && graph.is_adjacent(&self[self.len() - 1], &self[0]) => {
+                    SequenceID::HamCycle
+                }
+                true => SequenceID::HamChain,
+                false => SequenceID::Broken,
+            }
         }
     }
 
-    impl L1Norm for V2d {
-        fn l1norm(&self) -> i16 {
-            self.iter()
-                .map(|v| {
-                    let mask = v >> 15;
-                    (v ^ mask) - mask
-                })
-                .sum()
+    /// The graph a sequence is certified against: its vertices and which of them are adjacent.
+    pub trait Adjacency<V> {
+        /// Number of vertices in the graph.
+        fn order(&self) -> usize;
+        /// Check if vert is a vertex of the graph.
+        fn contains(&self, vert: &V) -> bool;
+        /// Check if lhs and rhs are adjacent vertices of the graph.
+        fn is_adjacent(&self, lhs: &V, rhs: &V) -> bool;
+        /// Displacement vector of vert from the origin, summed over the tour. Graphs without coordinates don't displace.
+        fn displacement(&self, _vert: &V) -> [i64; 3] {
+            [0, 0, 0]
         }
     }
 
-    /// Test that input vector is adjacent to self where the points are points in a 3d grid.
-    pub trait IsAdjacent {
-        /// Check if self is adjacent to another vertex.
-        fn is_adj_to(&self, other: V3d) -> bool;
+    /// 🪩 The discocube as an implicit adjacency oracle: nothing is stored, vertices are odd coordinates within max_absumv3d and adjacent along one axis.
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    pub struct Discocube {
+        order: usize,
+        max_absumv3d: ScalarXyz,
     }
 
-    impl IsAdjacent for V3d {
-        fn is_adj_to(&self, [x, y, z]: V3d) -> bool {
-            match self[0] - x + self[1] - y + self[2] - z {
-                n if n == 2 || n == -2 => true,
-                _ => false,
+    impl Discocube {
+        /// The discocube with `order` vertices whose L1-norms are at most `max_absumv3d`.
+        pub fn new(order: usize, max_absumv3d: ScalarXyz) -> Discocube {
+            Discocube {
+                order,
+                max_absumv3d,
             }
         }
+
+        /// Number of vertices, whichever the vertex type.
+        pub fn order(&self) -> usize {
+            self.order
+        }
     }
-}
 
-/// ✅ Certify if the solution is Hamiltonian.
-pub mod certify_solution_node {
-    use crate::graph::types::{V2d, V3d};
-    use itertools::Itertools;
+    impl Adjacency<V3d> for Discocube {
+        fn order(&self) -> usize {
+            self.order
+        }
 
-    use std::{
-        collections::{HashMap, HashSet},
-        fmt,
-    };
+        fn contains(&self, vert: &V3d) -> bool {
+            // Summed in i64: the L1-norm of a vertex read from a file can overflow `ScalarXyz`, and such a vertex is out of bounds.
+            vert.iter().all(|scalar| scalar % 2 != 0)
+                && vert
+                    .iter()
+                    .map(|&scalar| (scalar as i64).abs())
+                    .sum::<i64>()
+                    <= self.max_absumv3d as i64
+        }
 
-    #[derive(Debug, PartialEq)]
-    /// Enum describing possible ids of a solution: Broken, Chain, Cycle.
-    pub enum SequenceID {
-        Broken,
-        HamChain,
-        HamCycle,
+        fn is_adjacent(&self, lhs: &V3d, rhs: &V3d) -> bool {
+            lhs.is_adj_to(*rhs)
+        }
+
+        fn displacement(&self, &[x, y, z]: &V3d) -> [i64; 3] {
+            [x as i64, y as i64, z as i64]
+        }
     }
 
-    /// impl Display to print out SequenceID w/o debug.
-    impl fmt::Display for SequenceID {
-        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-            match self {
-                SequenceID::Broken => write!(f, "Broken"),
-                SequenceID::HamChain => write!(f, "HamChain"),
-                SequenceID::HamCycle => write!(f, "HamCycle"),
+    /// Nodes of the discocube are the ranks of its vertices, see `translate::rank`.
+    impl Adjacency<Node> for Discocube {
+        fn order(&self) -> usize {
+            self.order
+        }
+
+        fn contains(&self, &node: &Node) -> bool {
+            (node as usize) < self.order
+        }
+
+        fn is_adjacent(&self, &lhs: &Node, &rhs: &Node) -> bool {
+            match (unrank_unbounded(lhs as u64), unrank_unbounded(rhs as u64)) {
+                (Some(lhs), Some(rhs)) => lhs.is_adj_to(rhs),
+                _ => false,
             }
         }
     }
 
-    /// Certify if a sequence is a Hamiltonian cycle by:
-    /// Checking for duplicates
-    /// Check that solution length is equal to the order of the graph.
-    /// The sum of all displacement vectors used to construct the cycle is equal to [0, 0, 0]
-    /// Check that the current node is adjacent to the next node.
-    /// Check that the last node is adjacent to the first. If not is is a HamChain else HamCycle.
-    pub trait CertifyNode<SequenceID> {
-        /// Certify if a sequence is a Hamiltonian cycle by:
-        /// Checking for duplicates
-        /// Check that solution length is equal to the order of the graph.
-        /// The sum of all displacement vectors used to construct the cycle is equal to [0, 0, 0]
-        /// Check that the current node is adjacent to the next node.
-        /// Check that the last node is adjacent to the first. If not is is a HamChain else HamCycle.
-        fn certify(&mut self, adj: &HashMap<u32, HashSet<u32>>) -> SequenceID;
-    }
-
-    impl CertifyNode<SequenceID> for &mut Vec<u32> {
-        fn certify(&mut self, adj: &HashMap<u32, HashSet<u32>>) -> SequenceID {
-            if self.iter().duplicates().count() > 0 || self.len() != adj.len() {
-                return SequenceID::Broken;
-            }
-            match self
-                .windows(2)
-                .all(|window| adj[&window[0]].contains(&window[1]))
-            {
-                true if adj[&self[self.len() - 1]].contains(&self[0]) => SequenceID::HamCycle,
-                true => SequenceID::HamChain,
-                false => SequenceID::Broken,
-            }
+    /// An explicit adjacency list.
+    impl<V: Eq + Hash> Adjacency<V> for HashMap<V, HashSet<V>> {
+        fn order(&self) -> usize {
+            self.len()
+        }
+
+        fn contains(&self, vert: &V) -> bool {
+            self.contains_key(vert)
+        }
+
+        fn is_adjacent(&self, lhs: &V, rhs: &V) -> bool {
+            self.get(lhs)
+                .is_some_and(|neighbors| neighbors.contains(rhs))
         }
     }
 
-    impl CertifyNode<SequenceID> for &mut [u32; 12320] {
-        fn certify(&mut self, adj: &HashMap<u32, HashSet<u32>>) -> SequenceID {
-            if self.iter().duplicates().count() > 0 || self.len() != adj.len() {
-                return SequenceID::Broken;
-            }
-            match self
-                .windows(2)
-                .all(|window| adj[&window[0]].contains(&window[1]))
-            {
-                true if adj[&self[self.len() - 1]].contains(&self[0]) => SequenceID::HamCycle,
-                true => SequenceID::HamChain,
-                false => SequenceID::Broken,
-            }
+    /// 🗜️ Compressed sparse row adjacency of the nodes `0..order`: the sorted neighbors of node i are `neighbors[offsets[i]..offsets[i + 1]]`.
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    pub struct Csr {
+        offsets: Vec<usize>,
+        neighbors: Vec<Node>,
+    }
+
+    impl Csr {
+        /// Build from the neighbors of each node in order, node i being the i-th.
+        pub fn from_neighbors<I: IntoIterator<Item = Vec<Node>>>(adjacency: I) -> Csr {
+            let mut csr = Csr {
+                offsets: vec![0],
+                neighbors: Vec::new(),
+            };
+            adjacency.into_iter().for_each(|mut neighbors| {
+                neighbors.sort_unstable();
+                csr.neighbors.extend(neighbors);
+                csr.offsets.push(csr.neighbors.len());
+            });
+            csr
+        }
+
+        /// Sorted neighbors of node.
+        pub fn neighbors(&self, node: Node) -> &[Node] {
+            &self.neighbors[self.offsets[node as usize]..self.offsets[node as usize + 1]]
         }
     }
 
-    impl CertifyNode<SequenceID> for Vec<u32> {
-        fn certify(&mut self, adj: &HashMap<u32, HashSet<u32>>) -> SequenceID {
-            if self.iter().duplicates().count() > 0 || self.len() != adj.len() {
-                return SequenceID::Broken;
-            }
-            match self
-                .windows(2)
-                .all(|window| adj[&window[0]].contains(&window[1]))
-            {
-                true if adj[&self[self.len() - 1]].contains(&self[0]) => SequenceID::HamCycle,
-                true => SequenceID::HamChain,
-                false => SequenceID::Broken,
-            }
+    /// The nodes of an adjacency list must be `0..adj.len()`, missing nodes have no neighbors.
+    impl From<&HashMap<Node, HashSet<Node>>> for Csr {
+        fn from(adj: &HashMap<Node, HashSet<Node>>) -> Csr {
+            Csr::from_neighbors((0..adj.len() as Node).map(|node| {
+                adj.get(&node)
+                    .map(|neighbors| neighbors.iter().copied().collect())
+                    .unwrap_or_default()
+            }))
+        }
+    }
+
+    impl Adjacency<Node> for Csr {
+        fn order(&self) -> usize {
+            self.offsets.len() - 1
+        }
+
+        fn contains(&self, &node: &Node) -> bool {
+            (node as usize) < self.order()
+        }
+
+        fn is_adjacent(&self, &lhs: &Node, &rhs: &Node) -> bool {
+            self.contains(&lhs) && self.neighbors(lhs).binary_search(&rhs).is_ok()
         }
     }
 
@@ -1115,20 +1979,25 @@ pub mod certify_solution_node {
     /// In effect, the norm is a calculation of the Manhattan distance from the origin of the vector space.
     pub trait L1Norm {
         /// Calculate the L1-norm for a vector, i.e., the sum of the absolute value of each scalar x, y and or z.
-        fn l1norm(&self) -> i16;
+        fn l1norm(&self) -> ScalarXyz;
     }
 
     impl L1Norm for V3d {
-        fn l1norm(&self) -> i16 {
-            self.iter().map(|v| v.abs()).sum()
+        fn l1norm(&self) -> ScalarXyz {
+            self.iter()
+                .map(|v| {
+                    let mask = v >> (ScalarXyz::BITS - 1);
+                    (v ^ mask) - mask
+                })
+                .sum()
         }
     }
 
     impl L1Norm for V2d {
-        fn l1norm(&self) -> i16 {
+        fn l1norm(&self) -> ScalarXyz {
             self.iter()
                 .map(|v| {
-                    let mask = v >> 15;
+                    let mask = v >> (ScalarXyz::BITS - 1);
                     (v ^ mask) - mask
                 })
                 .sum()
@@ -1143,34 +2012,173 @@ pub mod certify_solution_node {
 
     impl IsAdjacent for V3d {
         fn is_adj_to(&self, [x, y, z]: V3d) -> bool {
-            match self[0] - x + self[1] - y + self[2] - z {
-                n if n == 2 || n == -2 => true,
-                _ => false,
+            // Exactly one axis differs, by 2.
+            matches!(
+                [
+                    self[0].abs_diff(x),
+                    self[1].abs_diff(y),
+                    self[2].abs_diff(z)
+                ],
+                [2, 0, 0] | [0, 2, 0] | [0, 0, 2]
+            )
+        }
+    }
+}
+
+/// 🔒 A tour which is known to be a Hamiltonian cycle of its discocube: woven by `weave()` or certified by `HamCycle::try_from()`.
+/// Grading, encoding and exporting take a HamCycle so they never meet a broken tour.
+pub mod ham_cycle {
+    use std::ops::Deref;
+
+    use super::{
+        certify_solution::{Certify, SequenceID},
+        graph_info_from_n::InfoN,
+    };
+    use crate::graph::{
+        error::WeaveError,
+        types::{Tour, Verts},
+        weave::check_n,
+    };
+
+    /// A Hamiltonian cycle of discocube n. Read it as a slice of vertices, take it apart with `into_tour()`.
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    pub struct HamCycle {
+        n: usize,
+        tour: Tour,
+    }
+
+    impl HamCycle {
+        /// Certify tour as a Hamiltonian cycle of discocube n. A broken tour is returned as `WeaveError::NotAHamCycle` with the report of what failed.
+        pub fn try_from(tour: Tour, n: usize) -> Result<HamCycle, WeaveError> {
+            check_n(n)?;
+            let report = tour.certify_report(n.get_order_from_n(), n.get_max_absumv());
+            match report.id {
+                SequenceID::HamCycle => Ok(HamCycle { n, tour }),
+                _ => Err(WeaveError::NotAHamCycle(Box::new(report))),
             }
         }
+
+        /// Wrap the tour woven by the algorithm for n, a Hamiltonian cycle by construction.
+        pub(crate) fn woven(tour: Tour, n: usize) -> HamCycle {
+            HamCycle { n, tour }
+        }
+
+        /// The n of the discocube.
+        pub fn n(&self) -> usize {
+            self.n
+        }
+
+        /// Number of vertices of the discocube, which is the length of the cycle.
+        pub fn order(&self) -> usize {
+            self.tour.len()
+        }
+
+        /// Give up the guarantee and get the tour.
+        pub fn into_tour(self) -> Tour {
+            self.tour
+        }
+    }
+
+    impl Deref for HamCycle {
+        type Target = Verts;
+
+        fn deref(&self) -> &Verts {
+            &self.tour
+        }
     }
 }
 
 /// Module for translating the solution into a string by using a list of 30 unique characters
 /// Goal: Either choose your own unique 30 chars or
 pub mod translate {
-    use itertools::{iproduct, Itertools};
-    use rayon::prelude::{IntoParallelRefIterator, ParallelIterator};
+    use rayon::prelude::*;
+
+    use super::prelude::{Node, Nodes, ScalarXyz, V3d, Vectors, Verts};
+    use crate::graph::{error::WeaveError, extras::iters::Uon};
+
+    /// Makes vertices based on radius, ordered by (l1norm, x, y, z).
+    pub fn make_vertices(order: usize) -> Vec<V3d> {
+        (0..order as u64)
+            .map(|idx| unrank_unbounded(idx).expect("the vertices of an order fit the coordinates"))
+            .collect()
+    }
 
-    use super::prelude::{InfoN, L1Norm, Node, Nodes, Vectors};
+    /// Index of the vertex in the (l1norm, x, y, z) order of `make_vertices` without making the vertices.
+    /// None if vert is not a vertex of discocube n: a scalar is even or its l1norm is above `2n + 1`.
+    ///
+    /// Writing each scalar as `±(2p + 1)` with `p >= 0`, a vertex lies on shell `m = p + q + r`, the shell at l1norm `2m + 3`.
+    /// Shells before it hold the order of discocube m. Within the shell the vertices are counted by x, then y, then z.
+    pub fn rank(vert: V3d, n: usize) -> Option<u64> {
+        rank_unbounded(vert).filter(|&rank| below_order(rank, n))
+    }
+
+    /// The vertex at index idx in the (l1norm, x, y, z) order of `make_vertices` without making the vertices. Inverse of `rank`.
+    /// None if idx is not below the order of discocube n, or if its vertex doesn't fit the coordinates.
+    pub fn unrank(idx: u64, n: usize) -> Option<V3d> {
+        below_order(idx, n).then(|| unrank_unbounded(idx)).flatten()
+    }
 
-    /// Makes vertices based on radius.
-    pub fn make_vertices(order: usize) -> Vec<[i16; 3]> {
-        let radius = order.get_radius_from_order();
-        let radius_plus_4 = radius + 4;
-        iproduct!(
-            (-radius..=radius).step_by(2),
-            (-radius..=radius).step_by(2),
-            (-radius..=radius).step_by(2)
-        )
-        .filter_map(|(x, y, z)| ([x, y, z].l1norm() < radius_plus_4).then_some([x, y, z]))
-        .sorted_by_key(|&vert| (vert.l1norm(), vert[0], vert[1]))
-        .collect::<Vec<_>>()
+    /// Whether idx is the index of a vertex of discocube n. Every index is when the order doesn't fit a u64.
+    fn below_order(idx: u64, n: usize) -> bool {
+        <u64 as Uon>::order_of(n as u64).is_none_or(|order| idx < order)
+    }
+
+    /// `rank` in whichever discocube vert is a vertex of: the vertices of discocube n are the first vertices of discocube n + 1 in this order.
+    /// None if a scalar is even, or if the index doesn't fit in a u64.
+    pub(crate) fn rank_unbounded(vert: V3d) -> Option<u64> {
+        if vert.iter().any(|scalar| scalar % 2 == 0) {
+            return None;
+        }
+        let [x, y, z] = vert.map(|scalar| scalar as i64);
+        let l1norm = x.abs() + y.abs() + z.abs();
+        let h = (l1norm - 1) / 2;
+        // The shell bounds h, so the counts within it below fit.
+        let shell = <u64 as Uon>::order_of(h as u64 - 1)?;
+        // Vertices with the same l1norm and a smaller x: 4 * (h + 1 - t) for each |x| = 2t - 1.
+        let t = (x.abs() + 1) / 2;
+        let before_x = match x < 0 {
+            true => 2 * (h - t) * (h - t + 1),
+            false => 2 * h * (h + 1) + 4 * (t - 1) * (h + 1) - 2 * (t - 1) * t,
+        };
+        // Vertices with the same l1norm and x and a smaller y: 2 for each odd y from -(s - 1) on.
+        let s = l1norm - x.abs();
+        let before_y = y + s - 1;
+        shell.checked_add((before_x + before_y) as u64 + (z > 0) as u64)
+    }
+
+    /// `unrank` in whichever discocube idx is an index of. None if the vertex doesn't fit the coordinates.
+    pub(crate) fn unrank_unbounded(idx: u64) -> Option<V3d> {
+        let m = idx.floor_n();
+        let shell = <u64 as Uon>::order_of(m).expect("the order of the floor of an index fits");
+        let (h, offset) = ((m + 1) as i64, (idx - shell) as i64);
+        // Largest j with 2 * j * (j + 1) <= k: the number of |x| blocks, from the outermost, before offset k.
+        let blocks_before = |k: i64| (((4 * (k / 2) + 1) as u64).isqrt() as i64 - 1) / 2;
+        let negative_x = 2 * h * (h + 1);
+        let (x, within_x) = match offset < negative_x {
+            true => {
+                let j = blocks_before(offset);
+                (-(2 * (h - j) - 1), offset - 2 * j * (j + 1))
+            }
+            // The blocks of positive x mirror those of negative x.
+            false => {
+                let mirrored = 2 * negative_x - 1 - offset;
+                let j = blocks_before(mirrored);
+                (
+                    2 * (h - j) - 1,
+                    4 * (j + 1) - 1 - (mirrored - 2 * j * (j + 1)),
+                )
+            }
+        };
+        let s = 2 * h + 1 - x.abs();
+        let y = within_x / 2 * 2 - (s - 1);
+        let z = match within_x % 2 {
+            0 => -(s - y.abs()),
+            _ => s - y.abs(),
+        };
+        match [x, y, z].map(ScalarXyz::try_from) {
+            [Ok(x), Ok(y), Ok(z)] => Some([x, y, z]),
+            _ => None,
+        }
     }
 
     /// Convert a vector of indices to vectors to those vectors.
@@ -1180,25 +2188,28 @@ pub mod translate {
 
     impl NodesToVectors for Nodes {
         fn to_vectors(&self) -> Vectors {
-            let verts = make_vertices(self.len());
-            self.par_iter().map(|&node| verts[node as usize]).collect()
+            self.par_iter()
+                .map(|&node| {
+                    unrank_unbounded(node as u64)
+                        .expect("the vertex of a node fits the coordinates")
+                })
+                .collect()
         }
     }
 
     /// Convert a vector of points to a vector of their indices in the vertices list.
+    /// Fails on the first point that isn't a vertex, or whose index doesn't fit in a `Node`.
     pub trait VectorsToNodes {
-        // Check which is faster?
-        fn to_nodes(&self) -> Nodes;
+        fn to_nodes(&self) -> Result<Nodes, WeaveError>;
     }
 
-    impl VectorsToNodes for Vectors {
-        fn to_nodes(&self) -> Nodes {
-            self.iter()
-                .enumerate()
-                .sorted_by_key(|&(_, v)| (v.l1norm(), v[0], v[1], v[2]))
-                .enumerate()
-                .sorted_by_key(|&(_, (i, _))| i)
-                .map(|(idx, _)| idx as Node)
+    impl VectorsToNodes for Verts {
+        fn to_nodes(&self) -> Result<Nodes, WeaveError> {
+            self.par_iter()
+                .map(|&vert| {
+                    let rank = rank_unbounded(vert).ok_or(WeaveError::NotAVertex(vert))?;
+                    Node::try_from(rank).map_err(|_| WeaveError::RankOverflow(rank))
+                })
                 .collect()
         }
     }
@@ -1214,11 +2225,13 @@ pub mod serialize_chars {
     use rayon::prelude::*;
 
     use super::{
-        prelude::{Solution, Tour},
+        ham_cycle::HamCycle,
+        prelude::{Solution, Tour, V3d},
         translate::VectorsToNodes,
     };
+    use crate::graph::error::WeaveError;
 
-    pub fn md([a, b, c]: [i16; 3], [x, y, z]: [i16; 3]) -> usize {
+    pub fn md([a, b, c]: V3d, [x, y, z]: V3d) -> usize {
         ((a - x).abs() + (b - y).abs() + (c - z).abs())
             .try_into()
             .unwrap()
@@ -1234,7 +2247,7 @@ pub mod serialize_chars {
     /// Will be used eventually to encode a sequence using a user's personal code.
     pub static VALID_CHARS: &str = r"!”#$%&’()*+,-./0123456789:;<=>?@ABCDEFGHIJKLMNOPQRSTUVWXYZ[\]^_`abcdefghijklmnopqrstuvwxyz{}~";
     lazy_static! {
-        static ref STR_VEC: HashMap<char, [i16; 3]> = {
+        static ref STR_VEC: HashMap<char, V3d> = {
             hash_map! {
                 'S' => [-1, -1, -1],
                 'X' => [2, 0, 0],
@@ -1245,7 +2258,7 @@ pub mod serialize_chars {
                 'z' => [0, 0, -2]
             }
         };
-        static ref VEC_STR: HashMap<[i16; 3], char> = {
+        static ref VEC_STR: HashMap<V3d, char> = {
             hash_map! {
                 [2, 0, 0] => 'X',
                 [-2, 0, 0] => 'x',
@@ -1337,24 +2350,24 @@ pub mod serialize_chars {
         }
     }
 
-    /// 🔁 Rotate the loop so that [1, 1, 1] is in the first position and loop[1] < loop[-1] after keying.
-    /// ```
-    /// let mut vecloop = vec![[3, 3, 1], [3, 1, 1], [1, 1, 1], [1, 1, -1]];
-    /// vecloop.keyed();
-    /// assert_eq!(vecloop, vec![[1, 1, 1], [1, 1, -1], [3, 3, 1], [3, 1, 1]]);
+    /// 🔁 Rotate the loop so that [-1, -1, -1] is in the first position and loop[1] < loop[-1] after keying.
+    /// ```ignore
+    /// let keyed = weave(1)?.keyed();
+    /// assert_eq!(keyed[0], [-1, -1, -1]);
     /// ```
     pub trait KeyLoop {
-        /// Rotate the loop so that [1, 1, 1] is in the first position and loop[1] < loop[-1] after keying.
+        /// Rotate the loop so that [-1, -1, -1] is in the first position and loop[1] < loop[-1] after keying.
         fn keyed(&self) -> Tour;
     }
 
-    impl KeyLoop for Tour {
+    /// Every discocube has [-1, -1, -1], so every Hamiltonian cycle can be keyed on it.
+    impl KeyLoop for HamCycle {
         fn keyed(&self) -> Tour {
-            let mut result = self.clone();
+            let mut result = self.to_vec();
             let idx = result
                 .par_iter()
                 .position_any(|&vert| vert == [-1, -1, -1])
-                .unwrap();
+                .expect("every discocube has [-1, -1, -1]");
             result.rotate_left(idx);
             if result[result.len() - 1] < result[1] {
                 result.rotate_left(1);
@@ -1368,7 +2381,7 @@ pub mod serialize_chars {
         fn add(self, prev: Self) -> Self;
     }
 
-    impl AddVectors for [i16; 3] {
+    impl AddVectors for V3d {
         fn add(self, [x, y, z]: Self) -> Self {
             [x + self[0], y + self[1], z + self[2]]
         }
@@ -1378,7 +2391,7 @@ pub mod serialize_chars {
         fn sub(self, prev: Self) -> Self;
     }
 
-    impl SubtractVectors for [i16; 3] {
+    impl SubtractVectors for V3d {
         fn sub(self, [x, y, z]: Self) -> Self {
             [x - self[0], y - self[1], z - self[2]]
         }
@@ -1399,6 +2412,7 @@ pub mod serialize_chars {
     }
 
     pub trait ChardDisplacementVector {
+        #[allow(clippy::wrong_self_convention)]
         fn as_chrds(self) -> String;
     }
 
@@ -1423,12 +2437,11 @@ pub mod serialize_chars {
         }
 
         fn to_string_with(&self, key: &str) -> String {
-            let str_chr: HashMap<String, char> = HashMap::from(
-                key.chars()
-                    .enumerate()
-                    .map(|(idx, key)| (CORNERS[idx].to_string(), key))
-                    .collect::<HashMap<String, char>>(),
-            );
+            let str_chr: HashMap<String, char> = key
+                .chars()
+                .enumerate()
+                .map(|(idx, key)| (CORNERS[idx].to_string(), key))
+                .collect();
             self.par_iter().map(|p| str_chr[p]).collect()
         }
     }
@@ -1438,7 +2451,8 @@ pub mod serialize_chars {
         fn encode_with(&self, key: &str) -> String;
     }
 
-    impl Encode for Solution {
+    /// Every discocube has [-1, -1, -1] to key the loop on, and the displacement between the vertices of a cycle is always an edge.
+    impl Encode for HamCycle {
         fn encode(&self) -> String {
             self.keyed().get_vdisps().as_chrds().pair().to_string()
         }
@@ -1455,7 +2469,9 @@ pub mod serialize_chars {
     pub trait Decode {
         fn decode(&self) -> Solution;
         fn decode_with(&self, key: String) -> Solution;
-        fn decode_to_node(&self) -> Vec<u32>;
+        fn decode_to_node(&self) -> Result<Vec<u32>, WeaveError>;
+        /// Same as `decode` for strings that may not be encodings, such as files read from disk: an empty string or a character that encodes no corner is an error instead of a panic.
+        fn try_decode(&self) -> Result<Solution, WeaveError>;
     }
 
     impl Decode for String {
@@ -1464,7 +2480,6 @@ pub mod serialize_chars {
                 vec![[-1, -1, -1]],
                 self.chars()
                     .flat_map(|letter| CHR_STR[&letter].unpair())
-                    .into_iter()
                     .map(|s| STR_VEC[&s])
                     .collect_vec()[..(self.len() * 2) - 1]
                     .to_vec(),
@@ -1478,22 +2493,32 @@ pub mod serialize_chars {
             .collect_vec()
         }
 
-        fn decode_to_node(&self) -> Vec<u32> {
+        fn decode_to_node(&self) -> Result<Vec<u32>, WeaveError> {
             self.decode().to_nodes()
         }
 
+        fn try_decode(&self) -> Result<Solution, WeaveError> {
+            match self.chars().find(|letter| !CHR_STR.contains_key(letter)) {
+                _ if self.is_empty() => {
+                    Err(WeaveError::MalformedTour("empty encoding".to_string()))
+                }
+                Some(letter) => Err(WeaveError::MalformedTour(format!(
+                    "{letter:?} encodes no corner"
+                ))),
+                None => Ok(self.decode()),
+            }
+        }
+
         fn decode_with(&self, key: String) -> Solution {
-            let decoder: HashMap<char, &str> = HashMap::from(
-                key.chars()
-                    .enumerate()
-                    .map(|(idx, c)| (c, CORNERS[idx]))
-                    .collect::<HashMap<char, &str>>(),
-            );
+            let decoder: HashMap<char, &str> = key
+                .chars()
+                .enumerate()
+                .map(|(idx, c)| (c, CORNERS[idx]))
+                .collect();
             [
                 vec![[-1, -1, -1]],
                 self.chars()
                     .flat_map(|letter| decoder[&letter].unpair())
-                    .into_iter()
                     .map(|s| STR_VEC[&s])
                     .collect_vec()[..(self.len() * 2) - 1]
                     .to_vec(),
@@ -1525,11 +2550,11 @@ pub mod serialize_chars {
     }
 
     pub trait GetVectors {
-        fn as_points(self) -> Solution;
+        fn as_points(&self) -> Solution;
     }
 
     impl GetVectors for String {
-        fn as_points(self) -> Solution {
+        fn as_points(&self) -> Solution {
             ['S'.to_string(), self[..self.len() - 1].to_string()]
                 .concat()
                 .chars()
@@ -1554,31 +2579,32 @@ pub mod serialize_chars {
 
 /// 📤 Module for exporting the solution to a .csv file where each row is x, y, z.
 pub mod serialize_csv {
-    use crate::graph::types::Solution;
-    use serde::Serialize;
+    use super::ham_cycle::HamCycle;
+    use crate::graph::types::ScalarXyz;
+    use serde::{Deserialize, Serialize};
     use std::error::Error;
 
     /// Simple 3-dimensional vector struct for serializing points to csv.
-    #[derive(Debug, Serialize)]
+    #[derive(Debug, Deserialize, Serialize)]
     #[serde(rename_all = "PascalCase")]
     pub(crate) struct CsvVector {
-        pub(crate) x: i16,
-        pub(crate) y: i16,
-        pub(crate) z: i16,
+        pub(crate) x: ScalarXyz,
+        pub(crate) y: ScalarXyz,
+        pub(crate) z: ScalarXyz,
     }
 
     /// Save solution to `file_path` as a `.csv` file with the columns headers `x`, `y`, `z` representing the scalar value for each axis.
     pub trait SerializeToCsv<T> {
         /// Save solution to `file_path` as a `.csv` file with the columns `x`, `y`, `z` for each axis.\
         /// A python module using `pandas` and `plotly` to create a 3d line plot is available [here](https://github.com/discocube/plot_solution).
-        /// ```
-        /// let solution = weave(2);
+        /// ```ignore
+        /// let solution = weave(2)?;
         /// solution.serialize_to_csv("documents/solutions/csv/solution_2.csv")
         /// ```
         fn serialize_to_csv(&self, file_path: &str) -> Result<(), Box<dyn Error>>;
     }
 
-    impl SerializeToCsv<Result<(), Box<dyn Error>>> for Solution {
+    impl SerializeToCsv<Result<(), Box<dyn Error>>> for HamCycle {
         fn serialize_to_csv(&self, file_path: &str) -> Result<(), Box<dyn Error>> {
             let file = std::fs::File::create(file_path)?;
             let mut writer = csv::Writer::from_writer(file);
@@ -1599,30 +2625,117 @@ pub mod serialize_csv {
     }
 }
 
-///! 🩺 TEST
-///!
-///!
-///!
-///!
-///!
-///! 🩺 Test overflow and expected outputs for n.get_some_info().
+// 🩺 TEST
+//
+//
+//
+//
+//
+// 🩺 Test overflow and expected outputs for n.get_some_info().
 #[cfg(test)]
 mod tests_graph_info_from_n {
-    use super::prelude::*;
+    use super::{
+        graph_info_from_n::{icbrt, CheckedInfoN, MAX_N},
+        prelude::*,
+    };
+    use crate::graph::error::WeaveError;
 
     #[test]
-    /// Test behavior if input is max_i16
+    /// Test behavior if input is max_i16: beyond MAX_N for i16 coordinates, which wrap, but not for i32 coordinates.
     fn test_info_n_with_max_i16() {
-        let max_i16 = std::i16::MAX as Count;
+        let max_i16 = i16::MAX as Count;
         assert_eq!(max_i16.loom_size() as i16, 16384);
-        assert_eq!(max_i16.get_max_absumv() as i16, -1);
-        assert_eq!(max_i16.get_radius(), -3);
+        #[cfg(not(feature = "i32"))]
+        {
+            assert!(max_i16 > MAX_N);
+            assert_eq!(max_i16.get_max_absumv(), -1);
+            assert_eq!(max_i16.get_radius(), -3);
+        }
+        #[cfg(feature = "i32")]
+        {
+            assert!(max_i16 <= MAX_N);
+            assert_eq!(max_i16.get_max_absumv(), 65535);
+            assert_eq!(max_i16.get_radius(), 65533);
+        }
         assert_eq!(max_i16.get_radius_from_order(), 57);
         assert_eq!(max_i16.get_n_from_order(), 29);
         assert_eq!(max_i16.get_order_from_n(), 46912496074752);
         assert_eq!(max_i16.get_spool_size(), 2147418112);
     }
 
+    #[test]
+    /// MAX_N is bound by the coordinates for i16 and by the order for i32.
+    fn test_max_n() {
+        #[cfg(not(feature = "i32"))]
+        assert_eq!(MAX_N, 8192);
+        let numerator = |n: Count| (4 * n).checked_mul(n + 1)?.checked_mul(n + 2);
+        assert!(numerator(MAX_N).is_some());
+        assert!(4 * MAX_N - 2 <= ScalarXyz::MAX as Count);
+        #[cfg(feature = "i32")]
+        assert_eq!(numerator(MAX_N + 1), None);
+    }
+
+    #[test]
+    /// Orders are inverted exactly, even where the float cube root is off by one, and counts that aren't orders are refused.
+    fn test_try_n_from_order() {
+        for n in (1..=2000)
+            .chain(MAX_N - 100..=MAX_N)
+            .chain([1_000_000, 1_500_000])
+        {
+            let order = n.get_order_from_n();
+            assert_eq!(order.try_n_from_order(), Ok(n));
+            assert_eq!(order.get_n_from_order(), n);
+            assert_eq!(
+                (order + 1).try_n_from_order(),
+                Err(WeaveError::NotAnOctahedralOrder(order + 1))
+            );
+        }
+        assert_eq!(
+            0.try_n_from_order(),
+            Err(WeaveError::NotAnOctahedralOrder(0))
+        );
+        assert_eq!(icbrt(Count::MAX as u128), 2642245);
+        assert_eq!(icbrt(u128::MAX), 6981463658331);
+        assert_eq!(icbrt(26), 2);
+        assert_eq!(icbrt(27), 3);
+    }
+
+    #[test]
+    /// Checked values match the unchecked ones until they no longer fit.
+    fn test_checked_info_n() {
+        let n: Count = 100;
+        assert_eq!(n.checked_loom_size(), Ok(n.loom_size()));
+        assert_eq!(n.checked_max_absumv(), Ok(n.get_max_absumv()));
+        assert_eq!(n.checked_radius(), Ok(n.get_radius()));
+        assert_eq!(n.checked_radius_usize(), Ok(n.get_radius_usize()));
+        assert_eq!(n.checked_order_from_n(), Ok(n.get_order_from_n()));
+        assert_eq!(n.get_order_from_n().checked_radius_from_order(), Ok(199));
+        assert_eq!(n.checked_spool_size(), Ok(n.get_spool_size()));
+        assert_eq!(n.checked_z_color_len(), Ok(n.z_color_len()));
+        let too_large = ScalarXyz::MAX as Count;
+        assert_eq!(
+            too_large.checked_max_absumv(),
+            Err(WeaveError::ArithmeticOverflow(too_large))
+        );
+        assert_eq!(
+            too_large.checked_radius(),
+            Err(WeaveError::ArithmeticOverflow(too_large))
+        );
+        assert!(too_large.checked_z_color_len().is_err());
+        assert_eq!(
+            too_large.checked_loom_size(),
+            Err(WeaveError::ArithmeticOverflow(too_large))
+        );
+        assert_eq!(
+            0.checked_radius_usize(),
+            Err(WeaveError::ArithmeticOverflow(0))
+        );
+        assert_eq!(
+            Count::MAX.checked_order_from_n(),
+            Err(WeaveError::ArithmeticOverflow(Count::MAX))
+        );
+    }
+
     #[test]
     /// Test behavior if input is max_i16
     fn test_info_n_pass() {
@@ -1685,15 +2798,66 @@ mod tests_color_yarn {
 /// 🩺 Test `prepare_yarn` and `cut_yarn`
 #[cfg(test)]
 mod tests_prepare_yarn {
-    // use super::prelude::*;
+    use super::prelude::*;
 
     #[test]
-    /// Test by getting the requested color and slice of that color and if it has mapped it to a Vec<[i16; 3]>.
+    /// Test by getting the requested color and slice of that color and if it has mapped it to a Vec<V3d>.
     fn test_prepare_yarn() {}
 
     #[test]
-    /// Test by cutting a sequence using pins.
-    fn test_cut_using() {}
+    /// Test by cutting a sequence using pins: the yarn of n = 3 at z = -3 cut by the pins of the first thread.
+    fn test_cut_using() {
+        let yarns = Yarns::color_spun(Spindle::spin_out(3));
+        let mut pins = vec![[1, 1, -3], [-1, 1, -3]];
+        assert_eq!(
+            yarns.prep(-3, 1, 12).chop(&mut pins),
+            vec![
+                vec![
+                    [-1, 1, -3],
+                    [-3, 1, -3],
+                    [-3, -1, -3],
+                    [-1, -1, -3],
+                    [-1, -3, -3],
+                    [1, -3, -3],
+                    [1, -1, -3],
+                    [3, -1, -3],
+                    [3, 1, -3]
+                ],
+                vec![[1, 1, -3], [1, 3, -3], [-1, 3, -3]]
+            ]
+        );
+        // No pins no cutting, only reversing.
+        assert_eq!(
+            yarns.prep(-5, 3, 4).chop(&mut vec![]),
+            vec![vec![[1, 1, -5], [1, -1, -5], [-1, -1, -5], [-1, 1, -5]]]
+        );
+    }
+
+    #[test]
+    /// The cuts of a yarn of 10 at pins 3 and 7: the yarn before the first pin is read backwards onto it, what follows it up to the next pin starts a new thread.
+    fn test_chop_spans() {
+        let cut = |start, end, reversed, pin| YarnCut {
+            span: Span {
+                start,
+                end,
+                reversed,
+            },
+            pin,
+        };
+        assert_eq!(
+            chop_spans(&[3, 7], 10),
+            vec![
+                cut(7, 10, false, Some(1)),
+                cut(4, 7, false, None),
+                cut(0, 4, true, Some(0))
+            ]
+        );
+        assert_eq!(
+            chop_spans(&[0, 7], 10),
+            vec![cut(7, 10, false, Some(1)), cut(0, 7, false, Some(0))]
+        );
+        assert_eq!(chop_spans(&[], 4), vec![cut(0, 4, true, None)]);
+    }
 }
 
 /// 🩺 Test mark ends by marking ends of a thread.
@@ -1731,34 +2895,44 @@ mod tests_pin_threads {
 /// 🩺 Test extend threads by constructing a loom extending its threads and checking if the result matches the expected output.
 #[cfg(test)]
 mod tests_extend_threads {
-    // use super::prelude::*;
+    use super::prelude::*;
 
     #[test]
+    /// Extend the first thread of n = 3 with the cut yarn from z = -3: the front pin is replaced by the reversed cut and the back pin by the cut.
     fn test_extend_threads() {
-        // let n = 2;
-        // let mut loom = Loom::with_capacity(n.loom_size());
-        // let yarns = Yarns::color_spun(Spindle::spin(n.spool_size()));
-        // loom.extend_threads(yarns.prep(-3, 1, 8).chop(&vec![]));
-        // assert_eq!(loom, [[[1, 1, -3], [1, -1, -3], [-1, -1, -3], [-1, 1, -3]]]);
-        // loom.extend_threads(yarns.prep(-1, 3, 0).chop(&vec![[1, 1, -1], [-1, 1, -1]]));
-        // assert_eq!(
-        //     loom,
-        //     vec![
-        //         vec![[1, 1, -3], [1, -1, -3], [-1, -1, -3], [-1, 1, -3]],
-        //         vec![
-        //             [-1, 1, -1],
-        //             [-3, 1, -1],
-        //             [-3, -1, -1],
-        //             [-1, -1, -1],
-        //             [-1, -3, -1],
-        //             [1, -3, -1],
-        //             [1, -1, -1],
-        //             [3, -1, -1],
-        //             [3, 1, -1]
-        //         ],
-        //         vec![[1, 1, -1], [1, 3, -1], [-1, 3, -1]]
-        //     ]
-        // );
+        let mut loom = vec![LoomThread::from([
+            [1, 1, -5],
+            [1, -1, -5],
+            [-1, -1, -5],
+            [-1, 1, -5],
+        ])];
+        let mut pins = loom.pin_thread_ends(-3);
+        let yarns = Yarns::color_spun(Spindle::spin_out(3));
+        loom.extend_threads(yarns.prep(-3, 1, 12).chop(&mut pins));
+        assert_eq!(
+            loom,
+            vec![LoomThread::from([
+                [-1, 3, -3],
+                [1, 3, -3],
+                [1, 1, -3],
+                [1, 1, -5],
+                [1, -1, -5],
+                [-1, -1, -5],
+                [-1, 1, -5],
+                [-1, 1, -3],
+                [-3, 1, -3],
+                [-3, -1, -3],
+                [-1, -1, -3],
+                [-1, -3, -3],
+                [1, -3, -3],
+                [1, -1, -3],
+                [3, -1, -3],
+                [3, 1, -3]
+            ])]
+        );
+        // Cuts that don't start at a thread end become new threads.
+        loom.extend_threads(vec![vec![[3, 3, -1], [1, 3, -1]]]);
+        assert_eq!(loom[1], LoomThread::from([[3, 3, -1], [1, 3, -1]]));
     }
 }
 
@@ -1769,54 +2943,330 @@ mod tests_mirror_loom {
     use std::collections::VecDeque;
 
     #[test]
-    /// Create a loom and see if the it one thread is mirrored.
+    /// Create a loom and see if the it one thread is mirrored, without storing the reflection.
     fn test_mirror_threads() {
         let mut loom = Loom::with_capacity(1);
-        let mut expected_loom = Loom::with_capacity(1);
-        loom.push(VecDeque::from(vec![
-            [1, 1, -1],
-            [1, -1, -1],
-            [-1, -1, -1],
-            [-1, 1, -1],
-        ]));
-        expected_loom.push(VecDeque::from(vec![
-            [1, 1, -1],
-            [1, -1, -1],
-            [-1, -1, -1],
-            [-1, 1, -1],
-            [-1, 1, 1],
-            [-1, -1, 1],
-            [1, -1, 1],
-            [1, 1, 1],
-        ]));
-        loom.mirror_threads();
-        assert_eq!(loom, expected_loom);
+        let half = vec![[1, 1, -1], [1, -1, -1], [-1, -1, -1], [-1, 1, -1]];
+        loom.push(VecDeque::from(half.clone()));
+        let mirrored = loom.mirror_threads();
+        assert_eq!(mirrored[0].half, half);
+        assert_eq!(mirrored[0].len(), 8);
+        assert_eq!(
+            mirrored[0].verts().collect::<Tour>(),
+            vec![
+                [1, 1, -1],
+                [1, -1, -1],
+                [-1, -1, -1],
+                [-1, 1, -1],
+                [-1, 1, 1],
+                [-1, -1, 1],
+                [1, -1, 1],
+                [1, 1, 1],
+            ]
+        );
+        assert_eq!(
+            HalfCycle::new(half.clone(), false)
+                .verts()
+                .collect::<Tour>(),
+            half
+        );
+        // Expanded into the storage of the half, which grows to exactly the cycle.
+        let expected = mirrored[0].verts().collect::<Tour>();
+        let tour = mirrored.into_iter().next().unwrap().into_tour();
+        assert_eq!((tour.len(), tour.capacity()), (8, 8));
+        assert_eq!(tour, expected);
+        let mut aligned = HalfCycle::new(half.clone(), true);
+        aligned.align_to(([-1, 1, 1], [-1, -1, 1])).unwrap();
+        let expected = aligned.verts().collect::<Tour>();
+        assert_eq!(aligned.into_tour(), expected);
+    }
+
+    #[test]
+    /// Aligning a half cycle to an edge reads the same as aligning its expanded vertices.
+    fn test_align_half_cycle() {
+        let mut cycle = HalfCycle::new(
+            vec![[1, 1, -1], [1, -1, -1], [-1, -1, -1], [-1, 1, -1]],
+            true,
+        );
+        let mut expected = cycle.verts().collect::<Tour>();
+        for edge in [
+            ([1, -1, 1], [1, 1, 1]),
+            ([-1, -1, -1], [-1, -1, 1]),
+            ([-1, 1, -1], [-1, 1, 1]),
+        ] {
+            cycle.align_to(edge).unwrap();
+            expected.align_to(edge).unwrap();
+            assert_eq!(cycle.verts().collect::<Tour>(), expected);
+        }
+        assert_eq!(cycle.half.len(), 4);
+    }
+}
+
+/// 🩺 Test that rotating and reversing spans matches rotating and reversing the items they cover.
+#[cfg(test)]
+mod tests_splice {
+    use super::prelude::{Span, Splice};
+
+    #[test]
+    fn test_rotate_and_reverse_spans() {
+        let items = (0..12).collect::<Vec<usize>>();
+        let spans = vec![
+            Span::new(0, 4),
+            Span::new(4, 9).reversed(),
+            Span::new(9, 12),
+        ];
+        let read = |spans: &Vec<Span>| {
+            spans
+                .iter()
+                .flat_map(|span| (0..span.len()).map(|k| items[span.idx(k)]))
+                .collect::<Vec<_>>()
+        };
+        let flat = read(&spans);
+        assert_eq!(spans.order(), flat.len());
+        for mid in 0..flat.len() {
+            let (mut rotated, mut expected) = (spans.clone(), flat.clone());
+            rotated.rotate_at(mid);
+            expected.rotate_left(mid);
+            assert_eq!(read(&rotated), expected);
+            rotated.reverse_spans();
+            expected.reverse();
+            assert_eq!(read(&rotated), expected);
+        }
+    }
+
+    #[test]
+    fn test_locate() {
+        let spans = vec![Span::new(3, 5), Span::new(0, 0), Span::new(7, 10)];
+        assert_eq!(spans.locate(1), Some((0, 1)));
+        assert_eq!(spans.locate(2), Some((2, 0)));
+        assert_eq!(spans.locate(5), None);
+        assert_eq!(Span::new(7, 10).reversed().offset_of(7), Some(2));
+        assert_eq!(Span::new(7, 10).offset_of(10), None);
     }
 }
 
 /// 🩺 Test AlignToEdge.
 #[cfg(test)]
 mod tests_merge_cycles {
-    use super::prelude::AlignToEdge;
+    use super::prelude::*;
+    use crate::graph::error::WeaveError;
+
+    #[test]
+    /// The first warp is bridged along x at the top of the weft, the following warps along z two levels lower each time.
+    fn test_bridges() {
+        let mut weft = Weft::empty(9.get_order_from_n());
+        assert_eq!(
+            weft.bridges(),
+            (([1, 1, 13], [3, 1, 13]), ([3, 3, 13], [1, 3, 13]))
+        );
+        weft.raise_bridge();
+        assert_eq!(
+            weft.bridges(),
+            (([1, 1, 9], [1, 1, 11]), ([3, 1, 11], [3, 1, 9]))
+        );
+        weft.raise_bridge();
+        assert_eq!(
+            weft.bridges(),
+            (([1, 1, 5], [1, 1, 7]), ([3, 1, 7], [3, 1, 5]))
+        );
+    }
+
+    #[test]
+    /// Splicing warps into the weft gives the same tour as rotating and reversing the whole weft for each warp.
+    fn test_weft_matches_rotate_and_reverse() {
+        for n in 1..=20 {
+            let mut loom = Loom::with_capacity(n.loom_size());
+            let yarns = Yarns::color_spun(Spindle::spin_out(n));
+            n.z_color_len().into_iter().for_each(|((z, color), len)| {
+                let mut pins = loom.pin_thread_ends(z);
+                loom.extend_threads(yarns.prep(z, color, len).chop(&mut pins));
+            });
+            let (mut weft, mut warps) = loom.mirror_threads().prepare_cycle_merging(n);
+            let mut expected = weft.get_woven();
+            for warp in warps.iter_mut() {
+                let warp_edges = warp.edges(weft.joined);
+                let weft_bridge = weft.edges().unwrap().bridge(&warp_edges).unwrap();
+                weft.align_to(weft_bridge).unwrap();
+                expected.align_to(weft_bridge).unwrap();
+                assert_eq!(weft.get_woven(), expected);
+                warp.align_to(warp_edges.bridge(&weft_bridge).unwrap())
+                    .unwrap();
+                expected.extend(warp.verts());
+                weft.join(warp);
+            }
+            assert_eq!(weft.get_woven(), expected);
+        }
+    }
 
     #[test]
     fn test_align_to() {
         let mut v = vec![0, 1, 2, 3, 4, 5];
-        v.align_to((4, 3));
+        v.align_to((4, 3)).unwrap();
         assert_eq!(v, vec![4, 5, 0, 1, 2, 3]);
-        v.align_to((0, 1));
+        v.align_to((0, 1)).unwrap();
         assert_eq!(v, vec![0, 5, 4, 3, 2, 1]);
         // Should give a different result as this case was removed as this doesn't occur in this algo.
-        v.align_to((1, 0));
+        v.align_to((1, 0)).unwrap();
         assert_ne!(v, vec![1, 2, 3, 4, 5, 0]);
         assert_eq!(v, vec![1, 0, 5, 4, 3, 2]);
     }
+
+    #[test]
+    /// Aligning to an edge whose ends aren't in the sequence is an error and leaves the sequence untouched.
+    fn test_align_to_missing_end() {
+        let mut v = vec![0, 1, 2, 3];
+        assert_eq!(v.align_to((3, 7)), Err(WeaveError::MisalignedWarp));
+        assert_eq!(v, vec![0, 1, 2, 3]);
+    }
+
+    #[test]
+    /// A non-edge has no parallel edge and two sets of edges without parallel edges have no bridge.
+    fn test_invalid_edge_and_missing_bridge() {
+        let not_an_edge = ([1, 1, 1], [3, 3, 1]);
+        assert_eq!(
+            not_an_edge.eadjs(),
+            Err(WeaveError::InvalidEdge(not_an_edge))
+        );
+        let weft_edges = WeftEdges::from([([1, 1, -1], [1, 1, 1])]);
+        let warp_edges = WarpEdges::from([([1, 5, -1], [1, 5, 1])]);
+        assert_eq!(
+            weft_edges.bridge(&warp_edges),
+            Err(WeaveError::MissingBridge { z: 0 })
+        );
+    }
 }
 
 /// 🩺 Test if the given sequences are broken.
 #[cfg(test)]
 mod tests_certify_solution {
-    use super::prelude::*;
+    use super::{
+        prelude::*,
+        translate::{rank, unrank, VectorsToNodes},
+    };
+    use crate::graph::weave::weave;
+    use std::collections::{HashMap, HashSet};
+
+    #[test]
+    /// Only vertices differing along exactly one axis by 2 are adjacent: signed differences can't cancel out.
+    fn test_is_adj_to() {
+        assert!([1, 1, 1].is_adj_to([1, 1, 3]));
+        assert!([1, 1, 1].is_adj_to([-1, 1, 1]));
+        assert!(![0, 0, 0].is_adj_to([2, 2, -2]));
+        assert!(![1, 1, -1].is_adj_to([-1, -1, 1]));
+        assert!(![1, 1, 1].is_adj_to([3, 3, 1]));
+        assert!(![1, 1, 1].is_adj_to([1, 1, 1]));
+        // Far apart, not wrapping around to a step of 2.
+        assert!(![ScalarXyz::MAX, 1, 1].is_adj_to([-ScalarXyz::MAX, 1, 1]));
+    }
+
+    #[test]
+    /// The sign mask shifts by the width of the scalar so the norm holds for i16 and i32 coordinates alike.
+    fn test_l1norm() {
+        assert_eq!([-3, 2, -1].l1norm(), 6);
+        assert_eq!([-5, 7].l1norm(), 12);
+        let max = ScalarXyz::MAX / 3;
+        assert_eq!([-max, max, -max].l1norm(), 3 * max);
+        #[cfg(feature = "i32")]
+        assert_eq!([-70_000, 0, 70_001].l1norm(), 140_001);
+    }
+
+    #[test]
+    /// A tour of the cube jumping across its diagonal is broken, and the report says where.
+    fn test_certify_report() {
+        let mut sol: Solution = vec![
+            [1, 1, -1],
+            [-1, -1, 1],
+            [-1, 1, 1],
+            [1, 1, 1],
+            [1, -1, 1],
+            [1, -1, -1],
+            [-1, -1, -1],
+            [-1, 1, -1],
+        ];
+        let report = sol.certify_report(8, 3);
+        assert_eq!(report.id, SequenceID::Broken);
+        assert_eq!(report.not_adjacent, Some((0, 1)));
+        assert!(report.closed && report.duplicates.is_empty() && report.out_of_bounds.is_empty());
+        assert_eq!(report.to_string(), "Broken | not adjacent at 0 and 1");
+        sol[1] = [1, 1, -1];
+        sol.push([-1, -1, -9]);
+        let report = sol.certify_report(8, 3);
+        assert_eq!(report.duplicates, vec![([1, 1, -1], vec![0, 1])]);
+        assert_eq!(report.out_of_bounds, vec![(8, [-1, -1, -9])]);
+        assert_eq!((report.len, report.displacement_sum), (9, [1, 1, -11]));
+        assert_eq!(sol.certify(8, 3), report.id);
+        // Vertices whose L1-norm overflows the scalar are out of bounds.
+        let far = [ScalarXyz::MAX, ScalarXyz::MAX, 1];
+        assert_eq!(
+            [[1, 1, 1], far].certify_report(8, 3).out_of_bounds,
+            vec![(1, far)]
+        );
+        sol.extend([[1, 1, -1], [-1, 1, 1]]);
+        assert_eq!(
+            sol.certify_report(8, 3).duplicates,
+            vec![([1, 1, -1], vec![0, 1, 9]), ([-1, 1, 1], vec![2, 10])]
+        );
+        assert_eq!(
+            weave(4).unwrap().certify_report(160, 9).to_string(),
+            "HamCycle"
+        );
+    }
+    #[test]
+    /// The same tour certifies the same whichever the oracle: coordinates or nodes on the implicit discocube, an adjacency list or CSR.
+    fn test_certify_in() {
+        let n = 3;
+        let discocube = Discocube::new(n.get_order_from_n(), n.get_max_absumv());
+        let solution = weave(n).unwrap();
+        let nodes = solution.to_nodes().unwrap();
+        let adj: HashMap<Node, HashSet<Node>> = (0..discocube.order() as Node)
+            .map(|node| {
+                let vert = unrank(node as u64, n).unwrap();
+                let neighbors = (0..3)
+                    .flat_map(|axis| {
+                        [-2, 2].map(|step| {
+                            let mut next = vert;
+                            next[axis] += step;
+                            next
+                        })
+                    })
+                    .filter(|next| discocube.contains(next))
+                    .map(|next| rank(next, n).unwrap() as Node)
+                    .collect();
+                (node, neighbors)
+            })
+            .collect();
+        let csr = Csr::from(&adj);
+        assert_eq!(solution.certify_in(&discocube), SequenceID::HamCycle);
+        assert_eq!(nodes.certify_in(&discocube), SequenceID::HamCycle);
+        assert_eq!(nodes.certify_in(&adj), SequenceID::HamCycle);
+        assert_eq!(nodes.certify_in(&csr), SequenceID::HamCycle);
+        let mut broken = nodes.clone();
+        broken.swap(1, 2);
+        broken.push(discocube.order() as Node);
+        let report = broken.certify_report_in(&csr);
+        assert_eq!(report, broken.certify_report_in(&adj));
+        assert_eq!(report, broken.certify_report_in(&discocube));
+        assert_eq!(report.not_adjacent, Some((0, 1)));
+        assert_eq!(report.out_of_bounds, vec![(80, 80)]);
+        // Any vertex type, any graph: the square.
+        let square: HashMap<char, HashSet<char>> = ["ab", "bc", "cd", "da"]
+            .iter()
+            .flat_map(|edge| {
+                let [lhs, rhs] = [edge.as_bytes()[0] as char, edge.as_bytes()[1] as char];
+                [(lhs, rhs), (rhs, lhs)]
+            })
+            .fold(HashMap::new(), |mut adj, (lhs, rhs)| {
+                adj.entry(lhs).or_insert_with(HashSet::new).insert(rhs);
+                adj
+            });
+        assert_eq!(
+            ['a', 'b', 'c', 'd'].certify_in(&square),
+            SequenceID::HamCycle
+        );
+        assert_eq!(['b', 'c', 'd'].certify_in(&square), SequenceID::Broken);
+        assert_eq!(['a', 'c', 'b', 'd'].certify_in(&square), SequenceID::Broken);
+    }
+
     #[test]
     fn test_certify_broken() {
         // not long enough.
@@ -1852,6 +3302,111 @@ mod tests_certify_solution {
     }
 }
 
+/// 🩺 Test rank and unrank against sorting the vertices.
+#[cfg(test)]
+mod tests_translate {
+    use super::{
+        graph_info_from_n::MAX_N,
+        prelude::*,
+        translate::{make_vertices, rank, unrank, NodesToVectors, VectorsToNodes},
+    };
+    use crate::graph::{error::WeaveError, weave::weave};
+    use itertools::{iproduct, Itertools};
+
+    #[test]
+    fn test_rank_unrank() {
+        for n in 1..=12 {
+            let radius = n.get_radius();
+            let sorted = iproduct!(
+                (-radius..=radius).step_by(2),
+                (-radius..=radius).step_by(2),
+                (-radius..=radius).step_by(2)
+            )
+            .map(|(x, y, z)| [x, y, z])
+            .filter(|vert| vert.l1norm() <= n.get_max_absumv())
+            .sorted_by_key(|&vert| (vert.l1norm(), vert[0], vert[1], vert[2]))
+            .collect::<Vec<_>>();
+            assert_eq!(make_vertices(n.get_order_from_n()), sorted);
+            sorted
+                .iter()
+                .enumerate()
+                .for_each(|(idx, &vert)| assert_eq!(rank(vert, n), Some(idx as u64)));
+            // The first vertex of the next shell is beyond discocube n, and so is its index.
+            let order = n.get_order_from_n() as u64;
+            let next = unrank(order, n + 1).unwrap();
+            assert_eq!(rank(next, n), None);
+            assert_eq!(rank(next, n + 1), Some(order));
+            assert_eq!(unrank(order, n), None);
+        }
+        // Far beyond what can be sorted, up to the last vertex of the largest discocube.
+        for idx in [4_000_000_000, 1 << 36, MAX_N.get_order_from_n() as u64 - 1] {
+            assert_eq!(rank(unrank(idx, MAX_N).unwrap(), MAX_N), Some(idx));
+        }
+        // No vertex of the largest discocube is past its order, nor is any index which doesn't fit the coordinates.
+        assert_eq!(unrank(MAX_N.get_order_from_n() as u64, MAX_N), None);
+        #[cfg(not(feature = "i32"))]
+        assert_eq!(unrank(u64::MAX, usize::MAX), None);
+        // Not vertices: an even scalar, or too far out for the index to fit.
+        assert_eq!(rank([2, 0, 0], 1), None);
+        assert_eq!(rank([1, 1, 0], 1), None);
+        #[cfg(feature = "i32")]
+        assert_eq!(rank([i32::MAX, 1, 1], usize::MAX), None);
+    }
+
+    #[test]
+    fn test_nodes_to_vectors_and_back() {
+        let solution = weave(8).unwrap();
+        let nodes = solution.to_nodes().unwrap();
+        assert!(nodes
+            .iter()
+            .all(|&node| node < 8.get_order_from_n() as Node));
+        assert_eq!(nodes.to_vectors(), *solution);
+        assert_eq!(
+            [[1, 1, 1], [1, 1, 2]].to_nodes(),
+            Err(WeaveError::NotAVertex([1, 1, 2]))
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests_ham_cycle {
+    use super::{
+        ham_cycle::HamCycle,
+        serialize_chars::{Decode, Encode},
+    };
+    use crate::graph::{error::WeaveError, weave::weave};
+
+    #[test]
+    /// Woven and certified cycles are the same, broken tours and wrong n are refused with what failed.
+    fn test_try_from() {
+        let woven = weave(3).unwrap();
+        assert_eq!((woven.n(), woven.order()), (3, 80));
+        assert_eq!(HamCycle::try_from(woven.to_vec(), 3), Ok(woven.clone()));
+        let mut broken = woven.to_vec();
+        broken.swap(1, 2);
+        match HamCycle::try_from(broken, 3) {
+            Err(WeaveError::NotAHamCycle(report)) => assert_eq!(report.not_adjacent, Some((0, 1))),
+            other => panic!("{other:?}"),
+        }
+        assert!(HamCycle::try_from(woven.to_vec(), 4).is_err());
+        assert_eq!(
+            HamCycle::try_from(woven.into_tour(), 0),
+            Err(WeaveError::InvalidN(0))
+        );
+    }
+
+    #[test]
+    /// The encoding of a cycle decodes to a tour which certifies as the same cycle, keyed.
+    fn test_encode_decode() {
+        let woven = weave(4).unwrap();
+        let decoded = HamCycle::try_from(woven.encode().decode(), 4).unwrap();
+        assert_eq!(decoded.encode(), woven.encode());
+        assert_eq!(woven.encode().try_decode(), Ok(woven.encode().decode()));
+        assert!(String::new().try_decode().is_err());
+        assert!("ab?".to_string().try_decode().is_err());
+    }
+}
+
 /// 🩺 Run weave to create solution and convert that solution to csv in root/test.csv, then check if file exists in specified location and then delete it.
 #[cfg(test)]
 mod tests_serialize_csv {
@@ -1860,7 +3415,7 @@ mod tests_serialize_csv {
 
     #[test]
     fn test_serialize_csvput() {
-        let solution = weave(1);
+        let solution = weave(1).unwrap();
         solution.serialize_to_csv("test.csv").unwrap();
         let path = Path::new("test.csv");
         assert!(path.exists() && path.is_file());
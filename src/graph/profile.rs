@@ -0,0 +1,329 @@
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    fmt,
+    sync::atomic::{AtomicBool, AtomicUsize, Ordering},
+    time::{Duration, Instant},
+};
+
+use serde::{Serialize, Serializer};
+
+use super::{
+    error::WeaveError,
+    ops::{ham_cycle::HamCycle, prelude::*},
+    weave::{weave_probed, MergeStrategy, Probe},
+};
+
+static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+static COUNTING: AtomicBool = AtomicBool::new(false);
+
+/// 🧮 The system allocator counting the bytes allocated and their high-water mark, for `weave_profiled()` to report the memory of each phase.\
+/// It only counts once installed as the global allocator of the binary, which costs a couple of atomic operations per allocation:
+///```ignore
+///#[global_allocator]
+///static ALLOC: CountingAlloc = CountingAlloc;
+///```
+pub struct CountingAlloc;
+
+impl CountingAlloc {
+    /// Bytes allocated and not freed yet, `None` unless installed.
+    pub fn allocated() -> Option<usize> {
+        COUNTING
+            .load(Ordering::Relaxed)
+            .then(|| ALLOCATED.load(Ordering::Relaxed))
+    }
+
+    /// Most bytes allocated at once since the last `reset_peak()`, `None` unless installed.
+    pub fn peak() -> Option<usize> {
+        COUNTING
+            .load(Ordering::Relaxed)
+            .then(|| PEAK.load(Ordering::Relaxed))
+    }
+
+    /// Start a new high-water mark from the bytes allocated now.
+    pub fn reset_peak() {
+        PEAK.store(ALLOCATED.load(Ordering::Relaxed), Ordering::Relaxed);
+    }
+
+    /// Flag the allocator as installed on its first allocation, so later ones only read the flag.
+    fn install() {
+        if !COUNTING.load(Ordering::Relaxed) {
+            COUNTING.store(true, Ordering::Relaxed);
+        }
+    }
+
+    fn add(size: usize) {
+        let allocated = ALLOCATED.fetch_add(size, Ordering::Relaxed) + size;
+        PEAK.fetch_max(allocated, Ordering::Relaxed);
+    }
+
+    fn sub(size: usize) {
+        ALLOCATED.fetch_sub(size, Ordering::Relaxed);
+    }
+}
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        CountingAlloc::install();
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            CountingAlloc::add(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        CountingAlloc::sub(layout.size());
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        CountingAlloc::install();
+        let ptr = System.alloc_zeroed(layout);
+        if !ptr.is_null() {
+            CountingAlloc::add(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = System.realloc(ptr, layout, new_size);
+        if !new_ptr.is_null() {
+            CountingAlloc::add(new_size);
+            CountingAlloc::sub(layout.size());
+        }
+        new_ptr
+    }
+}
+
+fn as_secs<S: Serializer>(duration: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_f64(duration.as_secs_f64())
+}
+
+/// ⏱️ One phase of the weave: its name, the level it was run for, if any, how long it took and the most bytes allocated at once while it ran.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct PhaseProfile {
+    pub phase: &'static str,
+    pub z: Option<ScalarXyz>,
+    #[serde(rename = "seconds", serialize_with = "as_secs")]
+    pub wall: Duration,
+    /// `None` unless `CountingAlloc` is the global allocator.
+    pub peak_bytes: Option<usize>,
+}
+
+/// 📋 Where the time and memory of a weave go: each phase in the order it ran, the levels of the loom phase by phase.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct WeaveProfile {
+    pub n: usize,
+    pub order: usize,
+    #[serde(serialize_with = "as_str")]
+    pub strategy: MergeStrategy,
+    #[serde(rename = "seconds", serialize_with = "as_secs")]
+    pub total: Duration,
+    pub peak_bytes: Option<usize>,
+    pub phases: Vec<PhaseProfile>,
+}
+
+fn as_str<S: Serializer>(strategy: &MergeStrategy, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.collect_str(strategy)
+}
+
+impl WeaveProfile {
+    /// Each phase with the levels it ran for added up: how long it took in all and its highest high-water mark.
+    pub fn by_phase(&self) -> Vec<PhaseProfile> {
+        self.phases.iter().fold(Vec::new(), |mut totals, phase| {
+            match totals
+                .iter_mut()
+                .find(|total: &&mut PhaseProfile| total.phase == phase.phase)
+            {
+                Some(total) => {
+                    total.wall += phase.wall;
+                    total.peak_bytes = total.peak_bytes.max(phase.peak_bytes);
+                }
+                None => totals.push(PhaseProfile {
+                    z: None,
+                    ..phase.clone()
+                }),
+            }
+            totals
+        })
+    }
+
+    /// The profile as json, durations in seconds.
+    pub fn to_json(&self) -> Result<String, WeaveError> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+}
+
+fn mib(bytes: Option<usize>) -> String {
+    bytes.map_or("-".to_string(), |bytes| {
+        format!("{:.2} MiB", bytes as f64 / (1 << 20) as f64)
+    })
+}
+
+/// Print the profile as a table: the phases added up over the levels with their share of the total, then each level phase by phase.
+impl fmt::Display for WeaveProfile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "| 🇳 {} | ⭕️ {} | 🌳 {} | 🕗 {:.10} | 💾 {} |",
+            self.n,
+            self.order,
+            self.strategy,
+            self.total.as_secs_f64(),
+            mib(self.peak_bytes)
+        )?;
+        writeln!(
+            f,
+            "| {:<22} | {:>14} | {:>6} | {:>12} |",
+            "PHASE", "SECONDS", "%", "PEAK"
+        )?;
+        for total in self.by_phase() {
+            writeln!(
+                f,
+                "| {:<22} | {:>14.10} | {:>6.2} | {:>12} |",
+                total.phase,
+                total.wall.as_secs_f64(),
+                100.0 * total.wall.as_secs_f64() / self.total.as_secs_f64(),
+                mib(total.peak_bytes)
+            )?;
+        }
+        writeln!(
+            f,
+            "| {:>6} | {:<16} | {:>14} | {:>12} |",
+            "Z", "PHASE", "SECONDS", "PEAK"
+        )?;
+        for phase in self.phases.iter().filter(|phase| phase.z.is_some()) {
+            writeln!(
+                f,
+                "| {:>6} | {:<16} | {:>14.10} | {:>12} |",
+                phase.z.unwrap(),
+                phase.phase,
+                phase.wall.as_secs_f64(),
+                mib(phase.peak_bytes)
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Time each phase of the weave as it runs.
+struct Profiler {
+    phases: Vec<PhaseProfile>,
+}
+
+impl Probe for Profiler {
+    fn phase<T>(
+        &mut self,
+        phase: &'static str,
+        z: Option<ScalarXyz>,
+        run: impl FnOnce() -> T,
+    ) -> T {
+        CountingAlloc::reset_peak();
+        let start = Instant::now();
+        let done = run();
+        self.phases.push(PhaseProfile {
+            phase,
+            z,
+            wall: start.elapsed(),
+            peak_bytes: CountingAlloc::peak(),
+        });
+        done
+    }
+}
+
+/// 🔬 Weave through `weave_with()`, timing each phase, and each level of the loom, and recording the most memory allocated while it ran.\
+/// The phases are those of the weave: `spin_out`, `color_spun`, `pin_thread_ends`, `prep`, `chop` and `extend_threads` for every level, `mirror_threads`, `prepare_cycle_merging` and `merge`.\
+/// Memory is only known when `CountingAlloc` is the global allocator. `weave_with()` runs the same phases through a probe that does nothing, so it isn't timed.
+///```ignore
+///let (solution, profile) = weave_profiled(100, MergeStrategy::Sequential)?;
+///println!("{profile}");
+///```
+pub fn weave_profiled(
+    n: usize,
+    strategy: MergeStrategy,
+) -> Result<(HamCycle, WeaveProfile), WeaveError> {
+    let start = Instant::now();
+    let mut profiler = Profiler { phases: Vec::new() };
+    let solution = weave_probed(n, strategy, &mut profiler)?;
+    let total = start.elapsed();
+    let phases = profiler.phases;
+    let profile = WeaveProfile {
+        n,
+        order: n.get_order_from_n(),
+        strategy,
+        total,
+        peak_bytes: phases.iter().filter_map(|phase| phase.peak_bytes).max(),
+        phases,
+    };
+    Ok((solution, profile))
+}
+
+// 🩺 TEST
+//
+// 🩺 Test that the profiled weave is the weave and that its phases and memory are recorded.
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::{ops::graph_info_from_n::InfoN, weave::weave_with};
+
+    #[test]
+    /// Every phase is recorded, the loom phases once per level, and the weave is the same with either strategy.
+    fn test_weave_profiled() {
+        let n = 6;
+        for strategy in [MergeStrategy::Sequential, MergeStrategy::TreeReduction] {
+            let (solution, profile) = weave_profiled(n, strategy).unwrap();
+            assert_eq!(solution, weave_with(n, strategy).unwrap());
+            assert_eq!(profile.order, n.get_order_from_n());
+            let levels = n.z_color_len().len();
+            let phases = profile.by_phase();
+            let names = phases.iter().map(|phase| phase.phase).collect::<Vec<_>>();
+            assert_eq!(
+                names,
+                [
+                    "spin_out",
+                    "color_spun",
+                    "pin_thread_ends",
+                    "prep",
+                    "chop",
+                    "extend_threads",
+                    "mirror_threads",
+                    "prepare_cycle_merging",
+                    "merge"
+                ]
+            );
+            assert_eq!(profile.phases.len(), 5 + 4 * levels);
+            assert!(phases.iter().map(|phase| phase.wall).sum::<Duration>() <= profile.total);
+            assert_eq!(
+                profile.to_string().lines().count(),
+                1 + 1 + names.len() + 1 + 4 * levels
+            );
+            let json: serde_json::Value =
+                serde_json::from_str(&profile.to_json().unwrap()).unwrap();
+            assert_eq!(
+                json["phases"].as_array().unwrap().len(),
+                profile.phases.len()
+            );
+            assert_eq!(json["strategy"], strategy.to_string());
+        }
+        assert_eq!(
+            weave_profiled(0, MergeStrategy::Sequential),
+            Err(WeaveError::InvalidN(0))
+        );
+    }
+
+    #[test]
+    /// The bytes allocated and the high-water mark follow what's added and taken off, without flagging the allocator as installed.
+    fn test_counting_alloc() {
+        let installed = COUNTING.load(Ordering::Relaxed);
+        CountingAlloc::add(1 << 20);
+        let allocated = ALLOCATED.load(Ordering::Relaxed);
+        CountingAlloc::reset_peak();
+        CountingAlloc::add(2 << 20);
+        CountingAlloc::sub(1 << 20);
+        assert!(PEAK.load(Ordering::Relaxed) >= allocated + (2 << 20));
+        CountingAlloc::sub(2 << 20);
+        assert_eq!(COUNTING.load(Ordering::Relaxed), installed);
+        assert_eq!(CountingAlloc::peak().is_some(), installed);
+    }
+}
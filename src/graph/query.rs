@@ -0,0 +1,278 @@
+use super::{
+    error::WeaveError,
+    ops::prelude::*,
+    strands::{MergeStrands, MirrorStrands, Strand, WeaveStrands, YarnIndex},
+    weave::check_n,
+};
+
+/// 🎯 The weave of n kept as the strands of its weft, each with the position in the tour at which it starts, so any position of the tour is read off without building it.
+/// Memory is proportional to the number of strands, quadratic in n, instead of to the order which is cubic: n = 1817 is 3.3 million strands for 8 billion vertices.
+/// Building it costs about as much as `weave_into()` without writing the tour, linear in the number of strands. Each query is then a binary search over the strands, both ways: from a position to its vertex and from a vertex to its position.
+#[derive(Clone, Debug)]
+pub struct WovenTour {
+    yarns: Yarns,
+    index: SpoolIndex,
+    strands: Strands,
+    // Position of the first vertex of each strand followed by the order.
+    starts: Vec<Count>,
+    // Indices of the strands ordered by elevation then by where they start on the yarn. The strands of a level don't overlap.
+    by_level: Vec<usize>,
+}
+
+/// 📍 Where a vertex lies along the tour and the vertices before and after it, the tour being a cycle.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TourPosition {
+    pub position: Count,
+    pub predecessor: V3d,
+    pub successor: V3d,
+}
+
+impl WovenTour {
+    /// Weave n as strands: the same tour as `weave()` and `weave_into()`.
+    pub fn new(n: usize) -> Result<WovenTour, WeaveError> {
+        check_n(n)?;
+        let (yarns, index, loom) = StrandLoom::weave_strands(n)?;
+        let strands = loom.mirror_strands().merge_strands(n, &index)?;
+        let starts = std::iter::once(0)
+            .chain(strands.iter().scan(0, |start, strand| {
+                *start += strand.len();
+                Some(*start)
+            }))
+            .collect();
+        let mut by_level = (0..strands.len()).collect::<Vec<_>>();
+        by_level.sort_unstable_by_key(|&idx| (strands[idx].z, strands[idx].span.start));
+        Ok(WovenTour {
+            yarns,
+            index,
+            strands,
+            starts,
+            by_level,
+        })
+    }
+
+    /// Number of vertices in the tour.
+    pub fn order(&self) -> Count {
+        self.starts[self.strands.len()]
+    }
+
+    /// The vertex at position k of the tour, if the tour is that long.
+    pub fn at(&self, k: Count) -> Option<V3d> {
+        self.locate(k)
+            .map(|(strand, offset)| self.strands[strand].vert(&self.yarns, offset))
+    }
+
+    /// Position of the vertex along the tour, if it is a vertex of the discocube.
+    /// The vertex is looked up on the yarn of each color and the strand covering it at its elevation found by binary search.
+    pub fn position(&self, [x, y, z]: V3d) -> Option<Count> {
+        [3, 1].into_iter().find_map(|color| {
+            let idx = self.index.yarn_idx(color, [x, y])?;
+            let candidate = self
+                .by_level
+                .partition_point(|&s| (self.strands[s].z, self.strands[s].span.start) <= (z, idx));
+            let strand = self.by_level[candidate.checked_sub(1)?];
+            let Strand {
+                z: level,
+                color: yarn,
+                span,
+            } = self.strands[strand];
+            (level == z && yarn == color)
+                .then(|| span.offset_of(idx))
+                .flatten()
+                .map(|offset| self.starts[strand] + offset)
+        })
+    }
+
+    /// Position of the vertex along the tour with its predecessor and successor, if it is a vertex of the discocube.
+    pub fn neighbors(&self, vert: V3d) -> Option<TourPosition> {
+        let (position, order) = (self.position(vert)?, self.order());
+        Some(TourPosition {
+            position,
+            predecessor: self.at((position + order - 1) % order)?,
+            successor: self.at((position + 1) % order)?,
+        })
+    }
+
+    /// Iterate over the tour from its first vertex.
+    pub fn iter(&self) -> TourIter<'_> {
+        self.iter_from(0)
+    }
+
+    /// Iterate over the tour from position k on. Skipping ahead with `nth()` or `step_by()` jumps straight to the position instead of reading the vertices in between.
+    pub fn iter_from(&self, k: Count) -> TourIter<'_> {
+        let (strand, offset) = self.locate(k).unwrap_or((self.strands.len(), 0));
+        TourIter {
+            woven: self,
+            strand,
+            offset,
+            position: k.min(self.order()),
+        }
+    }
+
+    /// Index of the strand holding position k and the offset of k within it.
+    /// Empty strands start where the next starts, so the last strand starting at or before k is the one holding it.
+    fn locate(&self, k: Count) -> Option<(usize, Count)> {
+        (k < self.order()).then(|| {
+            let strand = self.starts.partition_point(|&start| start <= k) - 1;
+            (strand, k - self.starts[strand])
+        })
+    }
+}
+
+/// 🚶 The vertices of a `WovenTour` in order, each read off its strand as it is reached.
+#[derive(Clone, Debug)]
+pub struct TourIter<'a> {
+    woven: &'a WovenTour,
+    strand: usize,
+    offset: Count,
+    position: Count,
+}
+
+impl Iterator for TourIter<'_> {
+    type Item = V3d;
+
+    fn next(&mut self) -> Option<V3d> {
+        let strands: &[Strand] = &self.woven.strands;
+        while self.offset >= strands.get(self.strand)?.len() {
+            self.strand += 1;
+            self.offset = 0;
+        }
+        let vert = strands[self.strand].vert(&self.woven.yarns, self.offset);
+        self.offset += 1;
+        self.position += 1;
+        Some(vert)
+    }
+
+    fn nth(&mut self, k: usize) -> Option<V3d> {
+        *self = self.woven.iter_from(self.position.saturating_add(k));
+        self.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.woven.order() - self.position;
+        (remaining, Some(remaining))
+    }
+}
+
+impl ExactSizeIterator for TourIter<'_> {}
+
+impl<'a> IntoIterator for &'a WovenTour {
+    type Item = V3d;
+    type IntoIter = TourIter<'a>;
+
+    fn into_iter(self) -> TourIter<'a> {
+        self.iter()
+    }
+}
+
+/// 🎯 The vertex at position k of `weave(n)` without building the tour, see `WovenTour`.\
+/// Each call weaves the strands again, which takes seconds at n = 1817: to read more than one position build a `WovenTour` once and query it.
+///```ignore
+///let woven = WovenTour::new(1817)?;
+///let vert = woven.at(4_000_000_000);
+///let samples = woven.iter().step_by(1_000_000).collect::<Tour>();
+///// A single position, once.
+///let vert = tour_at(1817, 4_000_000_000)?;
+///```
+pub fn tour_at(n: usize, k: Count) -> Result<V3d, WeaveError> {
+    let woven = WovenTour::new(n)?;
+    woven.at(k).ok_or(WeaveError::PositionOutOfRange {
+        k,
+        order: woven.order(),
+    })
+}
+
+/// 📍 The position of vert along `weave(n)` with its predecessor and successor, without building the tour, see `WovenTour`.\
+/// Each call weaves the strands again, which takes seconds at n = 1817: to look up more than one vertex build a `WovenTour` once and query it.
+///```ignore
+///let woven = WovenTour::new(1817)?;
+///let TourPosition { position, predecessor, successor } = woven.neighbors([1, 1, -1]).unwrap();
+///// A single vertex, once.
+///let TourPosition { position, predecessor, successor } = tour_position(1817, [1, 1, -1])?;
+///```
+pub fn tour_position(n: usize, vert: V3d) -> Result<TourPosition, WeaveError> {
+    WovenTour::new(n)?
+        .neighbors(vert)
+        .ok_or(WeaveError::VertexNotInTour(vert))
+}
+
+// 🩺 TEST
+//
+// 🩺 Test that reading positions off the strands gives the tour of `weave()`.
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::weave::weave;
+
+    #[test]
+    /// Every position, iterating and skipping ahead give the vertices of the weave.
+    fn test_woven_tour() {
+        for n in 1..=20 {
+            let solution = weave(n).unwrap();
+            let woven = WovenTour::new(n).unwrap();
+            assert_eq!(woven.order(), solution.len());
+            assert_eq!(woven.iter().len(), solution.len());
+            assert_eq!(woven.iter().collect::<Tour>(), *solution);
+            assert!((0..solution.len()).all(|k| woven.at(k) == Some(solution[k])));
+            assert_eq!(woven.at(solution.len()), None);
+            for step in [1, 7, 100] {
+                assert!(woven.iter_from(3).step_by(step).eq(solution
+                    .iter()
+                    .copied()
+                    .skip(3)
+                    .step_by(step)));
+            }
+        }
+    }
+
+    #[test]
+    /// Positions past the end of the tour are an error.
+    fn test_tour_at() {
+        let solution = weave(4).unwrap();
+        assert_eq!(tour_at(4, 17), Ok(solution[17]));
+        assert_eq!(
+            tour_at(4, solution.len()),
+            Err(WeaveError::PositionOutOfRange {
+                k: solution.len(),
+                order: solution.len()
+            })
+        );
+        assert_eq!(tour_at(0, 0), Err(WeaveError::InvalidN(0)));
+        let woven = WovenTour::new(4).unwrap();
+        assert_eq!(woven.iter_from(solution.len() + 5).next(), None);
+        assert_eq!(woven.iter().nth(usize::MAX), None);
+    }
+
+    #[test]
+    /// The position of every vertex is where the weave visits it, between the vertices visited before and after.
+    fn test_tour_position() {
+        for n in 1..=20 {
+            let solution = weave(n).unwrap();
+            let woven = WovenTour::new(n).unwrap();
+            let order = solution.len();
+            for (k, &vert) in solution.iter().enumerate() {
+                assert_eq!(
+                    woven.neighbors(vert),
+                    Some(TourPosition {
+                        position: k,
+                        predecessor: solution[(k + order - 1) % order],
+                        successor: solution[(k + 1) % order],
+                    })
+                );
+            }
+            let beyond = n.get_max_absumv();
+            for outside in [
+                [beyond, 1, 1],
+                [1, 1, beyond],
+                [1, beyond, -1],
+                [2, 1, -1],
+                [1, 1, 0],
+            ] {
+                assert_eq!(woven.position(outside), None);
+            }
+        }
+        assert_eq!(
+            tour_position(3, [1, 1, 9]),
+            Err(WeaveError::VertexNotInTour([1, 1, 9]))
+        );
+    }
+}
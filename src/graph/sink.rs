@@ -0,0 +1,180 @@
+use std::io::Write;
+
+use super::{
+    error::WeaveError,
+    ops::{serialize_csv::CsvVector, translate::rank_unbounded},
+    types::*,
+};
+
+/// 🕳️ Receives the vertices of a tour one at a time, in order, as `weave_into()` streams them out.
+pub trait TourSink {
+    /// Receive the next vertex of the tour.
+    fn put(&mut self, vert: V3d) -> Result<(), WeaveError>;
+    /// Called once after the last vertex of the tour has been put.
+    fn finish(&mut self) -> Result<(), WeaveError> {
+        Ok(())
+    }
+}
+
+/// Collect the tour into a solution. Same result as `weave()`.
+impl TourSink for Solution {
+    fn put(&mut self, vert: V3d) -> Result<(), WeaveError> {
+        self.push(vert);
+        Ok(())
+    }
+}
+
+/// 🔢 Collect the tour as the ranks of its vertices in the (l1norm, x, y, z) order of `make_vertices`, see `translate::rank`: 4 bytes per vertex.
+/// A rank that doesn't fit in a `Node` is an error instead of wrapping, use `Vec<u64>` for discocubes above `u32::MAX` vertices.
+impl TourSink for Nodes {
+    fn put(&mut self, vert: V3d) -> Result<(), WeaveError> {
+        let node = rank_unbounded(vert).ok_or(WeaveError::NotAVertex(vert))?;
+        self.push(Node::try_from(node).map_err(|_| WeaveError::RankOverflow(node))?);
+        Ok(())
+    }
+}
+
+/// 🔢 Collect the tour as the ranks of its vertices, 8 bytes per vertex: for any discocube.
+impl TourSink for Vec<u64> {
+    fn put(&mut self, vert: V3d) -> Result<(), WeaveError> {
+        self.push(rank_unbounded(vert).ok_or(WeaveError::NotAVertex(vert))?);
+        Ok(())
+    }
+}
+
+/// 📤 Write the tour as `X,Y,Z` rows: the same file `SerializeToCsv` writes, with the first vertex repeated at the end to close the loop.
+pub struct CsvSink<W: Write> {
+    writer: csv::Writer<W>,
+    first: Option<V3d>,
+}
+
+impl<W: Write> CsvSink<W> {
+    /// Stream csv rows into `writer`.
+    pub fn new(writer: W) -> CsvSink<W> {
+        CsvSink {
+            writer: csv::Writer::from_writer(writer),
+            first: None,
+        }
+    }
+
+    /// Recover the writer once the tour has been streamed.
+    pub fn into_inner(self) -> Result<W, WeaveError> {
+        self.writer
+            .into_inner()
+            .map_err(|error| WeaveError::Io(error.to_string()))
+    }
+}
+
+impl CsvSink<std::fs::File> {
+    /// Stream csv rows into a new file at `file_path`.
+    pub fn create(file_path: &str) -> Result<CsvSink<std::fs::File>, WeaveError> {
+        Ok(CsvSink::new(std::fs::File::create(file_path)?))
+    }
+}
+
+impl<W: Write> TourSink for CsvSink<W> {
+    fn put(&mut self, [x, y, z]: V3d) -> Result<(), WeaveError> {
+        self.first.get_or_insert([x, y, z]);
+        Ok(self.writer.serialize(CsvVector { x, y, z })?)
+    }
+
+    fn finish(&mut self) -> Result<(), WeaveError> {
+        if let Some([x, y, z]) = self.first {
+            self.writer.serialize(CsvVector { x, y, z })?;
+        }
+        Ok(self.writer.flush()?)
+    }
+}
+
+/// 💾 Write the tour as raw little-endian `ScalarXyz` triples: 6 bytes per vertex, or 12 with the `i32` feature.
+pub struct BinarySink<W: Write> {
+    writer: W,
+}
+
+impl<W: Write> BinarySink<W> {
+    /// Stream raw vertices into `writer`. Wrap files in a `BufWriter`.
+    pub fn new(writer: W) -> BinarySink<W> {
+        BinarySink { writer }
+    }
+}
+
+impl<W: Write> TourSink for BinarySink<W> {
+    fn put(&mut self, vert: V3d) -> Result<(), WeaveError> {
+        vert.iter()
+            .try_for_each(|scalar| self.writer.write_all(&scalar.to_le_bytes()))?;
+        Ok(())
+    }
+
+    fn finish(&mut self) -> Result<(), WeaveError> {
+        Ok(self.writer.flush()?)
+    }
+}
+
+/// 📞 Hand each vertex to a closure.
+pub struct CallbackSink<F: FnMut(V3d)>(pub F);
+
+impl<F: FnMut(V3d)> TourSink for CallbackSink<F> {
+    fn put(&mut self, vert: V3d) -> Result<(), WeaveError> {
+        (self.0)(vert);
+        Ok(())
+    }
+}
+
+// 🩺 TEST
+//
+// 🩺 Test what each sink receives and writes.
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    /// Raw little-endian scalars, 6 bytes a vertex.
+    #[cfg(not(feature = "i32"))]
+    fn test_binary_sink() {
+        let mut sink = BinarySink::new(Vec::new());
+        sink.put([1, -1, 3]).unwrap();
+        sink.put([-3, 2, 1]).unwrap();
+        sink.finish().unwrap();
+        assert_eq!(sink.writer, [1, 0, 255, 255, 3, 0, 253, 255, 2, 0, 1, 0]);
+    }
+
+    #[test]
+    /// Raw little-endian scalars, 12 bytes a vertex.
+    #[cfg(feature = "i32")]
+    fn test_binary_sink() {
+        let mut sink = BinarySink::new(Vec::new());
+        sink.put([1, -1, 70000]).unwrap();
+        sink.finish().unwrap();
+        assert_eq!(sink.writer, [1, 0, 0, 0, 255, 255, 255, 255, 112, 17, 1, 0]);
+    }
+
+    #[test]
+    /// Ranks of the vertices, and an error once a rank doesn't fit in a `Node`.
+    fn test_node_sink() {
+        let mut nodes = Nodes::new();
+        nodes.put([1, 1, -1]).unwrap();
+        nodes.put([1, 1, 1]).unwrap();
+        assert_eq!(nodes, [6, 7]);
+        let far = [1, 1, 32767];
+        assert_eq!(
+            nodes.put(far),
+            Err(WeaveError::RankOverflow(rank_unbounded(far).unwrap()))
+        );
+        let mut wide = Vec::<u64>::new();
+        wide.put(far).unwrap();
+        assert_eq!(wide, [rank_unbounded(far).unwrap()]);
+        assert_eq!(wide.put([2, 1, 1]), Err(WeaveError::NotAVertex([2, 1, 1])));
+        assert!(wide[0] > u32::MAX as u64);
+    }
+
+    #[test]
+    /// Header, each vertex and the first vertex again to close the loop.
+    fn test_csv_sink() {
+        let mut sink = CsvSink::new(Vec::new());
+        sink.put([1, -1, 3]).unwrap();
+        sink.put([-3, 2, 1]).unwrap();
+        sink.finish().unwrap();
+        let written = String::from_utf8(sink.into_inner().unwrap()).unwrap();
+        assert_eq!(written, "X,Y,Z\n1,-1,3\n-3,2,1\n1,-1,3\n");
+    }
+}
@@ -0,0 +1,459 @@
+use std::collections::BTreeMap;
+
+use itertools::Itertools;
+
+use super::{error::WeaveError, ops::prelude::*};
+
+/// 🧵 A strand is a run of yarn cut from one level, kept as the range it covers on the colored yarn instead of as the vertices themselves.
+/// Every level of the loom is a slice of the same spun yarn, so a thread is a handful of strands and the whole weave can be described in a space proportional to the spool size instead of the order.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Strand {
+    /// Elevation of the strand. Mirrored strands have a positive z.
+    pub z: ScalarXyz,
+    /// Color of the yarn the strand was cut from.
+    pub color: ColorIdx,
+    /// Range of the yarn covered by the strand and the direction it is read in.
+    pub span: Span,
+}
+
+impl Spanned for Strand {
+    fn span(&self) -> Span {
+        self.span
+    }
+
+    fn with_span(self, span: Span) -> Strand {
+        Strand { span, ..self }
+    }
+}
+
+impl Strand {
+    /// Number of vertices in the strand.
+    pub fn len(&self) -> Count {
+        self.span.len()
+    }
+
+    /// Whether the strand has no vertices.
+    pub fn is_empty(&self) -> bool {
+        self.span.is_empty()
+    }
+
+    /// The same strand traversed in the other direction.
+    pub fn reversed(self) -> Strand {
+        self.with_span(self.span.reversed())
+    }
+
+    /// The strand reflected along the z-axis: `[x, y, z]` -> `[x, y, -z]`.
+    pub fn mirrored(self) -> Strand {
+        Strand { z: -self.z, ..self }
+    }
+
+    /// The k-th vertex of the strand.
+    pub fn vert(&self, yarns: &Yarns, k: Count) -> V3d {
+        let yarn = &yarns[&self.color];
+        let idx = self.span.idx(k);
+        [yarn[[idx, 0]], yarn[[idx, 1]], self.z]
+    }
+
+    /// The first vertex of the strand.
+    pub fn first(&self, yarns: &Yarns) -> V3d {
+        self.vert(yarns, 0)
+    }
+
+    /// The last vertex of the strand.
+    pub fn last(&self, yarns: &Yarns) -> V3d {
+        self.vert(yarns, self.len() - 1)
+    }
+
+    /// Iterate over the vertices of the strand in order.
+    pub fn verts<'a>(&self, yarns: &'a Yarns) -> impl Iterator<Item = V3d> + 'a {
+        let strand = *self;
+        let yarn = &yarns[&strand.color];
+        (0..strand.len()).map(move |k| {
+            let idx = strand.span.idx(k);
+            [yarn[[idx, 0]], yarn[[idx, 1]], strand.z]
+        })
+    }
+}
+
+/// 🗂️ Look up where a 2d vertex lies on the blue or red yarn.
+pub trait YarnIndex {
+    /// Build the lookup from the spun yarn before it is colored.
+    fn indexed(spool: &Spindle) -> Self;
+    /// Index of `[x, y]` on the yarn of the given color.
+    fn yarn_idx(&self, color: ColorIdx, vert: V2d) -> Option<Count>;
+}
+
+/// 🗺️ Where each 2d vertex lies on the spool, kept in a grid over the odd coordinates of the square holding the spool.
+/// Looking a vertex up is arithmetic instead of hashing, and building the index writes the grid once: the spool of n = 1817 has 6.6 million vertices.
+#[derive(Clone, Debug)]
+pub struct SpoolIndex {
+    // Largest absolute coordinate on the spool.
+    radius: i64,
+    // Index on the spool of each cell of the grid, row by row along x, ABSENT_IDX where there's no vertex.
+    cells: Vec<Count>,
+}
+
+/// Marks a cell of the grid with no vertex of the spool.
+const ABSENT_IDX: Count = Count::MAX;
+
+impl SpoolIndex {
+    /// Cell of `[x, y]`, if it's an odd vertex within the grid. Computed in i64 so that any coordinates can be looked up.
+    fn cell(&self, [x, y]: [i64; 2]) -> Option<usize> {
+        let side = self.radius + 1;
+        let [col, row] = [x, y].map(|scalar| {
+            (scalar % 2 != 0 && scalar.abs() <= self.radius).then(|| (scalar + self.radius) / 2)
+        });
+        Some((col? * side + row?) as usize)
+    }
+}
+
+impl YarnIndex for SpoolIndex {
+    fn indexed(spool: &Spindle) -> SpoolIndex {
+        let radius = spool
+            .iter()
+            .flatten()
+            .map(|&scalar| (scalar as i64).abs())
+            .max()
+            .unwrap_or_default();
+        let mut index = SpoolIndex {
+            radius,
+            cells: vec![ABSENT_IDX; ((radius + 1) * (radius + 1)) as usize],
+        };
+        for (idx, &[x, y]) in spool.iter().enumerate() {
+            if let Some(cell) = index.cell([x as i64, y as i64]) {
+                index.cells[cell] = idx;
+            }
+        }
+        index
+    }
+
+    fn yarn_idx(&self, color: ColorIdx, [x, y]: V2d) -> Option<Count> {
+        let [x, y] = [x as i64, y as i64];
+        // Red is blue reflected and shifted by 2 along y: undo it to look up blue.
+        let cell = match color {
+            3 => self.cell([x, y]),
+            _ => self.cell([-x, 2 - y]),
+        }?;
+        Some(self.cells[cell]).filter(|&idx| idx != ABSENT_IDX)
+    }
+}
+
+/// 🧶 A sequence of strands read as one sequence of vertices.
+/// Rotating and reversing come from `Splice`.
+pub trait StrandSequence {
+    /// Iterate over the vertices of the sequence.
+    fn verts<'a>(&'a self, yarns: &'a Yarns) -> Box<dyn Iterator<Item = V3d> + 'a>;
+    /// Position of vertex in the sequence.
+    fn position(&self, index: &SpoolIndex, vert: V3d) -> Option<Count>;
+}
+
+impl StrandSequence for Strands {
+    fn verts<'a>(&'a self, yarns: &'a Yarns) -> Box<dyn Iterator<Item = V3d> + 'a> {
+        Box::new(self.iter().flat_map(move |strand| strand.verts(yarns)))
+    }
+
+    fn position(&self, index: &SpoolIndex, [x, y, z]: V3d) -> Option<Count> {
+        let [blue, red] = [index.yarn_idx(3, [x, y]), index.yarn_idx(1, [x, y])];
+        let mut offset = 0;
+        for strand in self {
+            if strand.z == z {
+                let yarn_idx = match strand.color {
+                    3 => blue,
+                    _ => red,
+                };
+                if let Some(k) = yarn_idx.and_then(|idx| strand.span.offset_of(idx)) {
+                    return Some(offset + k);
+                }
+            }
+            offset += strand.len();
+        }
+        None
+    }
+}
+
+/// 🪜 Build the loom level by level as strands: the same pinning, chopping and extending of `weave()` but done on ranges of yarn.
+pub trait WeaveStrands {
+    /// Spin, color and build each thread of the loom as strands. Returns the yarns and the index needed to read the strands.
+    fn weave_strands(n: usize) -> Result<(Yarns, SpoolIndex, StrandLoom), WeaveError>;
+}
+
+impl WeaveStrands for StrandLoom {
+    fn weave_strands(n: usize) -> Result<(Yarns, SpoolIndex, StrandLoom), WeaveError> {
+        let spool = Spindle::spin_out(n);
+        let index = SpoolIndex::indexed(&spool);
+        let yarns = Yarns::color_spun(spool);
+        let mut loom = StrandLoom::with_capacity(n.loom_size());
+        for ((z, color), len) in n.z_color_len() {
+            // 📌 Pin each end of each thread: where on this level's yarn the end continues.
+            let mut pins = loom
+                .iter()
+                .enumerate()
+                .flat_map(|(t, thread)| {
+                    [
+                        (thread[0].first(&yarns), t, true),
+                        (thread[thread.len() - 1].last(&yarns), t, false),
+                    ]
+                })
+                .map(|([x, y, _], t, front)| {
+                    index
+                        .yarn_idx(color, [x, y])
+                        .filter(|&idx| idx < len)
+                        .map(|idx| (idx, t, front))
+                        .ok_or(WeaveError::MissingPin { z })
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            pins.sort_unstable();
+            let positions = pins.iter().map(|&(idx, _, _)| idx).collect::<Vec<_>>();
+            // 🔪 Chop the yarn at the pins, the same cuts as `SegmentYarn::chop`, and 🧮 extend the pinned end of each thread with its cut.
+            let mut new_threads = Vec::new();
+            for YarnCut { span, pin } in chop_spans(&positions, len) {
+                let cut = Strand { z, color, span };
+                match pin.map(|p| pins[p]) {
+                    Some((_, t, true)) => loom[t].push_front(cut.reversed()),
+                    Some((_, t, false)) => loom[t].push_back(cut),
+                    None => new_threads.push(cut),
+                }
+            }
+            loom.extend(
+                new_threads
+                    .into_iter()
+                    .filter(|strand| !strand.is_empty())
+                    .map(|strand| StrandThread::from([strand])),
+            );
+        }
+        Ok((yarns, index, loom))
+    }
+}
+
+/// 🪞 Turn each thread into a cycle by following it with its reflection. Only the strands are mirrored, the vertices are never copied.
+pub trait MirrorStrands {
+    /// Turn each thread into a cycle of strands: ◡ + ◠ = ◯.
+    fn mirror_strands(self) -> Vec<Strands>;
+}
+
+impl MirrorStrands for StrandLoom {
+    fn mirror_strands(self) -> Vec<Strands> {
+        self.into_iter()
+            .map(|thread| {
+                let mirror = thread
+                    .iter()
+                    .rev()
+                    .map(|strand| strand.mirrored().reversed())
+                    .collect_vec();
+                thread.into_iter().chain(mirror).collect()
+            })
+            .collect()
+    }
+}
+
+/// 🪢 Merge the cycles of strands into one, joining them at the same bridges as `weave()`.
+pub trait MergeStrands {
+    /// Join each warp into the weft and return the strands of the weft.
+    fn merge_strands(self, n: usize, index: &SpoolIndex) -> Result<Strands, WeaveError>;
+}
+
+impl MergeStrands for Vec<Strands> {
+    fn merge_strands(self, n: usize, index: &SpoolIndex) -> Result<Strands, WeaveError> {
+        let warps = self.len().saturating_sub(1);
+        let mut links = StrandLinks::new(self);
+        // Only the bookkeeping of the elevation of the next bridge, and the bridges computed from it, is taken from the weft.
+        let mut tracker = Weft::empty(n.get_order_from_n());
+        // Same as aligning the weft to its bridge, which starts it at the bridge's lhs, when there's no warp to join.
+        let mut start = 0;
+        for _ in 0..warps {
+            let (weft_bridge, warp_bridge) = tracker.bridges();
+            start = links.splice(index, weft_bridge, warp_bridge)?;
+            tracker.raise_bridge();
+        }
+        Ok(links.walk(start))
+    }
+}
+
+/// 🔗 The strands of all the cycles of the loom linked end to end, so a warp is spliced into the weft by relinking four ends
+/// instead of rotating and reversing the weft: merging takes a lookup per bridge instead of a pass over the weft.
+/// End `2 * s` is the first vertex of strand s and end `2 * s + 1` its last.
+struct StrandLinks {
+    strands: Strands,
+    // The end each end is linked to: of the strand before or after it in its cycle.
+    links: Vec<usize>,
+    // Strands by elevation then by where they start on the yarn, to find the strand holding a vertex. The strands of a level don't overlap.
+    by_level: BTreeMap<(ScalarXyz, Count), usize>,
+}
+
+impl StrandLinks {
+    /// Link the strands of each cycle into a ring. Empty strands hold no vertex and are left out.
+    fn new(cycles: Vec<Strands>) -> StrandLinks {
+        let (mut strands, mut links) = (Strands::new(), Vec::new());
+        for cycle in cycles {
+            let first = strands.len();
+            strands.extend(cycle.into_iter().filter(|strand| !strand.is_empty()));
+            let last = strands.len();
+            for s in first..last {
+                let prev = if s == first { last - 1 } else { s - 1 };
+                let next = if s + 1 == last { first } else { s + 1 };
+                links.extend([2 * prev + 1, 2 * next]);
+            }
+        }
+        let by_level = strands
+            .iter()
+            .enumerate()
+            .map(|(s, strand)| ((strand.z, strand.span.start), s))
+            .collect();
+        StrandLinks {
+            strands,
+            links,
+            by_level,
+        }
+    }
+
+    /// The strand holding vert and the offset of vert within it, same as `WovenTour::position`.
+    fn locate(&self, index: &SpoolIndex, [x, y, z]: V3d) -> Option<(usize, Count)> {
+        [3, 1].into_iter().find_map(|color| {
+            let idx = index.yarn_idx(color, [x, y])?;
+            let (&(level, _), &s) = self.by_level.range(..=(z, idx)).next_back()?;
+            let strand = self.strands[s];
+            (level == z && strand.color == color)
+                .then(|| strand.span.offset_of(idx))
+                .flatten()
+                .map(|offset| (s, offset))
+        })
+    }
+
+    /// Split strand s into its first k vertices and the rest, which becomes a new strand linked after it.
+    fn split(&mut self, s: usize, k: Count) {
+        let strand = self.strands[s];
+        let (head, tail) = strand.span.split_at(k);
+        let t = self.strands.len();
+        self.strands.push(strand.with_span(tail));
+        self.strands[s] = strand.with_span(head);
+        self.by_level.insert((strand.z, head.start), s);
+        self.by_level.insert((strand.z, tail.start), t);
+        let after = self.links[2 * s + 1];
+        self.links.extend([2 * s + 1, after]);
+        self.links[after] = 2 * t + 1;
+        self.links[2 * s + 1] = 2 * t;
+    }
+
+    /// The ends linking the two vertices of the edge, the strand holding both split between them. None if they aren't linked.
+    fn ends(&mut self, index: &SpoolIndex, (lhs, rhs): Edge) -> Option<(usize, usize)> {
+        let (s, k) = self.locate(index, lhs)?;
+        let (other, other_k) = self.locate(index, rhs)?;
+        if s == other && k.abs_diff(other_k) == 1 {
+            self.split(s, k.max(other_k));
+            return self.ends(index, (lhs, rhs));
+        }
+        let ends_at = |s: usize, k: Count| {
+            let len = self.strands[s].len();
+            [
+                (k == 0).then_some(2 * s),
+                (k + 1 == len).then_some(2 * s + 1),
+            ]
+        };
+        ends_at(s, k)
+            .into_iter()
+            .flatten()
+            .cartesian_product(ends_at(other, other_k).into_iter().flatten())
+            .find(|&(end, other_end)| self.links[end] == other_end)
+    }
+
+    /// Same as aligning the weft to its bridge and the warp to its own, then appending the warp: the two bridges are unlinked and their ends linked across.
+    /// Returns the end of the weft's lhs, where the weft starts once the warp has been appended.
+    fn splice(
+        &mut self,
+        index: &SpoolIndex,
+        weft_bridge: Edge,
+        warp_bridge: Edge,
+    ) -> Result<usize, WeaveError> {
+        let (lhs, rhs) = self
+            .ends(index, weft_bridge)
+            .ok_or(WeaveError::MisalignedWarp)?;
+        let (warp_lhs, warp_rhs) = self
+            .ends(index, warp_bridge)
+            .ok_or(WeaveError::MisalignedWarp)?;
+        for (end, other) in [(rhs, warp_lhs), (warp_rhs, lhs)] {
+            self.links[end] = other;
+            self.links[other] = end;
+        }
+        Ok(lhs)
+    }
+
+    /// The strands of the ring holding end start in order, from the vertex at start away from the end it's linked to.
+    fn walk(self, start: usize) -> Strands {
+        let mut strands = Strands::with_capacity(self.strands.len());
+        let mut end = start;
+        loop {
+            let strand = self.strands[end / 2];
+            strands.push(match end % 2 {
+                0 => strand,
+                _ => strand.reversed(),
+            });
+            end = self.links[end ^ 1];
+            if end == start || strands.len() == self.strands.len() {
+                return strands;
+            }
+        }
+    }
+}
+
+// 🩺 TEST
+//
+// 🩺 Test that moving strands around gives the same sequence as moving the vertices themselves.
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Yarns and index for n = 3 with a sequence of three strands cut from them.
+    fn strands() -> (Yarns, SpoolIndex, Strands) {
+        let spool = Spindle::spin_out(3);
+        let index = SpoolIndex::indexed(&spool);
+        let yarns = Yarns::color_spun(spool);
+        let strand = |z, color, start, end, reversed| Strand {
+            z,
+            color,
+            span: Span {
+                start,
+                end,
+                reversed,
+            },
+        };
+        let strands = vec![
+            strand(-1, 3, 0, 5, false),
+            strand(-3, 1, 2, 9, true),
+            strand(3, 1, 2, 9, false),
+        ];
+        (yarns, index, strands)
+    }
+
+    #[test]
+    /// Rotating and reversing strands matches rotating and reversing their vertices.
+    fn test_rotate_and_reverse_strands() {
+        let (yarns, _, strands) = strands();
+        let verts = strands.verts(&yarns).collect::<Tour>();
+        assert_eq!(strands.order(), verts.len());
+        for mid in 0..verts.len() {
+            let (mut rotated, mut expected) = (strands.clone(), verts.clone());
+            rotated.rotate_at(mid);
+            rotated.reverse_spans();
+            expected.rotate_left(mid);
+            expected.reverse();
+            assert_eq!(rotated.verts(&yarns).collect::<Tour>(), expected);
+        }
+    }
+
+    #[test]
+    /// The position of each vertex is found from its coordinates alone.
+    fn test_position() {
+        let (yarns, index, strands) = strands();
+        strands
+            .verts(&yarns)
+            .enumerate()
+            .for_each(|(idx, vert)| assert_eq!(strands.position(&index, vert), Some(idx)));
+        assert_eq!(strands.position(&index, [1, 1, 5]), None);
+        // Off the spool, even scalars and coordinates which would overflow once shifted aren't on either yarn.
+        for vert in [[1, 13], [2, 1], [1, -ScalarXyz::MAX], [ScalarXyz::MAX, 1]] {
+            assert_eq!(index.yarn_idx(3, vert), None);
+            assert_eq!(index.yarn_idx(1, vert), None);
+        }
+        assert_eq!(index.yarn_idx(3, [-1, 1]), Some(0));
+    }
+}
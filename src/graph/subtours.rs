@@ -0,0 +1,499 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+};
+
+use rayon::prelude::*;
+
+use super::{
+    error::WeaveError,
+    ops::{certify_solution::IsAdjacent, translate::rank_unbounded},
+    types::{Edges, Subtours, Tour, V3d},
+};
+
+/// Marks a vertex not yet given to a subtour.
+const ABSENT: usize = usize::MAX;
+
+/// The two neighbors of each vertex of a set of subtours. A vertex on a subtour of two vertices has the same neighbor twice.
+type Neighbors = HashMap<V3d, [V3d; 2]>;
+
+/// ✂️ Split an edge set of the discocube, e.g. a 2-factor or a damaged tour, into the subtours it is made of.
+pub trait Decompose {
+    /// Each cycle is returned as a tour, each path as a tour from one of its ends.
+    /// Fails on a pair of vertices which isn't an edge of the discocube and on a vertex with more than two edges.
+    fn decompose(&self) -> Result<Subtours, WeaveError>;
+}
+
+impl Decompose for Edges {
+    fn decompose(&self) -> Result<Subtours, WeaveError> {
+        let mut adjacent: HashMap<V3d, Vec<V3d>> = HashMap::with_capacity(self.len());
+        for &(lhs, rhs) in self {
+            if !lhs.is_adj_to(rhs) {
+                return Err(WeaveError::InvalidEdge((lhs, rhs)));
+            }
+            // Both orientations of an edge are the same edge.
+            if adjacent.get(&lhs).is_some_and(|verts| verts.contains(&rhs)) {
+                continue;
+            }
+            for (vert, other) in [(lhs, rhs), (rhs, lhs)] {
+                let verts = adjacent.entry(vert).or_default();
+                verts.push(other);
+                if verts.len() > 2 {
+                    return Err(WeaveError::BranchingVertex(vert));
+                }
+            }
+        }
+        // Walk the paths from their ends first so that what is left are cycles.
+        let mut starts = adjacent.keys().copied().collect::<Vec<_>>();
+        starts.sort_unstable_by_key(|vert| (adjacent[vert].len(), *vert));
+        let mut subtours = Subtours::new();
+        for start in starts {
+            if !adjacent.contains_key(&start) {
+                continue;
+            }
+            let mut subtour = vec![start];
+            let mut vert = start;
+            while let Some(next) = adjacent
+                .remove(&vert)
+                .and_then(|verts| verts.into_iter().find(|next| adjacent.contains_key(next)))
+            {
+                subtour.push(next);
+                vert = next;
+            }
+            subtours.push(subtour);
+        }
+        Ok(subtours)
+    }
+}
+
+/// 🪢 Join subtours into one cycle by bridges: an edge of one subtour parallel to an edge of another, one unit apart.
+/// The two edges are swapped for the two which connect their ends, the same as `merge_cycles` does for the warps of the loom.
+pub trait MergeSubtours {
+    /// Merge until one cycle remains, which is returned as a tour.
+    /// A subtour whose ends are adjacent is a cycle, closed by that edge. Fails with `WeaveError::MergeObstructed` when a subtour is open or when no bridge joins any two of those left.
+    fn merge_subtours(&self) -> Result<Tour, WeaveError>;
+
+    /// Merge as a reduction tree, on all threads: each round every cycle looks for a bridge to a neighbouring cycle with a smaller index, all cycles in parallel.
+    /// The bridges found hook the cycles into trees which are each joined into one cycle at once, so the count of cycles falls round after round.
+    /// Same failures as `merge_subtours`. Vertices are indexed by their rank: subtours with a vertex which can't be ranked, such as one with a scalar which isn't odd, are left to `merge_subtours`.
+    fn merge_parallel(&self) -> Result<Tour, WeaveError>;
+}
+
+impl MergeSubtours for Subtours {
+    fn merge_subtours(&self) -> Result<Tour, WeaveError> {
+        if self.iter().any(|subtour| is_open(subtour)) {
+            return Err(obstructed(self.clone()));
+        }
+        let mut neighbors = Neighbors::with_capacity(self.iter().map(Vec::len).sum());
+        let mut owner = HashMap::with_capacity(neighbors.capacity());
+        let mut edges = Vec::with_capacity(neighbors.capacity());
+        for (id, subtour) in self.iter().enumerate() {
+            for (idx, &vert) in subtour.iter().enumerate() {
+                let prev = subtour[(idx + subtour.len() - 1) % subtour.len()];
+                let next = subtour[(idx + 1) % subtour.len()];
+                if neighbors.insert(vert, [prev, next]).is_some() {
+                    return Err(WeaveError::BranchingVertex(vert));
+                }
+                owner.insert(vert, id);
+                edges.push((vert, next));
+            }
+        }
+        let mut cycles = Cycles::new(self.len());
+        // An edge with no bridge to another cycle never gets one as cycles only grow, so each edge is looked at once, along with the edges each join adds.
+        while let Some((lhs, rhs)) = edges.pop() {
+            if cycles.remaining == 1 {
+                break;
+            }
+            if !neighbors[&lhs].contains(&rhs) {
+                continue;
+            }
+            let cycle = cycles.find(owner[&lhs]);
+            let bridge = parallels((lhs, rhs)).find(|&(other_lhs, other_rhs)| {
+                owner
+                    .get(&other_lhs)
+                    .is_some_and(|&other| cycles.find(other) != cycle)
+                    && neighbors[&other_lhs].contains(&other_rhs)
+            });
+            if let Some((other_lhs, other_rhs)) = bridge {
+                relink(&mut neighbors, lhs, rhs, other_lhs);
+                relink(&mut neighbors, rhs, lhs, other_rhs);
+                relink(&mut neighbors, other_lhs, other_rhs, lhs);
+                relink(&mut neighbors, other_rhs, other_lhs, rhs);
+                cycles.union(cycle, owner[&other_lhs]);
+                edges.extend([(lhs, other_lhs), (rhs, other_rhs)]);
+            }
+        }
+        let mut cycles = walk(
+            |vert| neighbors[&vert],
+            self.iter().map(|subtour| subtour[0]),
+            cycles,
+        );
+        match cycles.len() {
+            1 => Ok(cycles.pop().unwrap_or_default()),
+            _ => Err(obstructed(cycles)),
+        }
+    }
+
+    fn merge_parallel(&self) -> Result<Tour, WeaveError> {
+        if self.iter().any(|subtour| is_open(subtour)) {
+            return Err(obstructed(self.clone()));
+        }
+        let Some(ranks) = self
+            .par_iter()
+            .map(|subtour| subtour.iter().map(|&vert| rank_unbounded(vert)).collect())
+            .collect::<Option<Vec<Vec<u64>>>>()
+        else {
+            return self.merge_subtours();
+        };
+        // Vertices are indexed densely by the position of their rank among the ranks of the subtours, so the tables are sized by the count of vertices however far they are from the origin.
+        let mut sorted = ranks.iter().flatten().copied().collect::<Vec<_>>();
+        sorted.par_sort_unstable();
+        sorted.dedup();
+        let dense =
+            |vert: V3d| -> Option<usize> { sorted.binary_search(&rank_unbounded(vert)?).ok() };
+        let ranked = |vert: V3d| dense(vert).expect("the vertices of the subtours are ranked");
+        let ats = self
+            .par_iter()
+            .map(|subtour| subtour.iter().map(|&vert| ranked(vert)).collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        // The neighbors and the subtour of each vertex by dense index.
+        let mut neighbors = vec![[[0; 3]; 2]; sorted.len()];
+        let mut owner = vec![ABSENT; sorted.len()];
+        for (id, subtour) in self.iter().enumerate() {
+            for (idx, &vert) in subtour.iter().enumerate() {
+                let at = ats[id][idx];
+                if owner[at] != ABSENT {
+                    return Err(WeaveError::BranchingVertex(vert));
+                }
+                owner[at] = id;
+                neighbors[at] = [
+                    subtour[(idx + subtour.len() - 1) % subtour.len()],
+                    subtour[(idx + 1) % subtour.len()],
+                ];
+            }
+        }
+        let owner_of = |vert: V3d| -> Option<usize> { dense(vert).map(|at| owner[at]) };
+        let mut cycles = Cycles::new(self.len());
+        while cycles.remaining > 1 {
+            let roots = (0..self.len())
+                .map(|id| cycles.find(id))
+                .collect::<Vec<_>>();
+            let mut members = vec![Vec::new(); self.len()];
+            roots
+                .iter()
+                .enumerate()
+                .for_each(|(id, &root)| members[root].push(id));
+            let hooks = members
+                .par_iter()
+                .enumerate()
+                .filter_map(|(root, ids)| {
+                    ids.iter()
+                        .flat_map(|&id| self[id].iter().zip(&ats[id]))
+                        .find_map(|(&lhs, &at)| {
+                            // Each edge once, from its smaller end.
+                            neighbors[at]
+                                .iter()
+                                .filter(|&&rhs| lhs < rhs)
+                                .find_map(|&rhs| {
+                                    parallels((lhs, rhs)).find_map(|(other_lhs, other_rhs)| {
+                                        let other = roots[owner_of(other_lhs)?];
+                                        (other < root
+                                            && neighbors[ranked(other_lhs)].contains(&other_rhs))
+                                        .then_some((
+                                            root,
+                                            other,
+                                            (lhs, rhs),
+                                            (other_lhs, other_rhs),
+                                        ))
+                                    })
+                                })
+                        })
+                })
+                .collect::<Vec<_>>();
+            // Each edge is swapped at most once per round: a hook whose edge is taken waits for the next round.
+            let mut taken = HashSet::with_capacity(2 * hooks.len());
+            let mut joined = false;
+            for (root, other, edge, other_edge) in hooks {
+                // Parallel edges keep their ends in the same order, so lhs stays next to other_lhs.
+                let [edge, other_edge] =
+                    [edge, other_edge].map(|(lhs, rhs)| (lhs.min(rhs), lhs.max(rhs)));
+                if taken.contains(&edge) || taken.contains(&other_edge) {
+                    continue;
+                }
+                taken.extend([edge, other_edge]);
+                let ((lhs, rhs), (other_lhs, other_rhs)) = (edge, other_edge);
+                for (vert, old, new) in [
+                    (lhs, rhs, other_lhs),
+                    (rhs, lhs, other_rhs),
+                    (other_lhs, other_rhs, lhs),
+                    (other_rhs, other_lhs, rhs),
+                ] {
+                    if let Some(slot) = neighbors[ranked(vert)]
+                        .iter_mut()
+                        .find(|slot| **slot == old)
+                    {
+                        *slot = new;
+                    }
+                }
+                cycles.union(other, root);
+                joined = true;
+            }
+            if !joined {
+                break;
+            }
+        }
+        let mut cycles = walk(
+            |vert| neighbors[ranked(vert)],
+            self.iter().map(|subtour| subtour[0]),
+            cycles,
+        );
+        match cycles.len() {
+            1 => Ok(cycles.pop().unwrap_or_default()),
+            _ => Err(obstructed(cycles)),
+        }
+    }
+}
+
+/// A subtour is open unless its ends are adjacent, closing it into a cycle.
+fn is_open(subtour: &[V3d]) -> bool {
+    subtour.len() < 2 || !subtour[subtour.len() - 1].is_adj_to(subtour[0])
+}
+
+/// What stopped the merging of subtours.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Obstruction {
+    /// The subtours left, merged as far as they went: either one of them is open or no bridge joins any two of them.
+    pub subtours: Subtours,
+}
+
+/// impl Display to print the count and the lengths of the subtours left.
+impl fmt::Display for Obstruction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let open = self
+            .subtours
+            .iter()
+            .filter(|subtour| is_open(subtour))
+            .count();
+        let lens = self
+            .subtours
+            .iter()
+            .take(8)
+            .map(Vec::len)
+            .collect::<Vec<_>>();
+        write!(f, "{} subtours of lengths {lens:?}", self.subtours.len())?;
+        match open {
+            0 => write!(f, " with no bridge between any two"),
+            open => write!(f, " of which {open} open"),
+        }
+    }
+}
+
+fn obstructed(subtours: Subtours) -> WeaveError {
+    WeaveError::MergeObstructed(Box::new(Obstruction { subtours }))
+}
+
+/// The edges parallel to edge one unit apart, along either of the other two axes.
+fn parallels((lhs, rhs): (V3d, V3d)) -> impl Iterator<Item = (V3d, V3d)> {
+    let axis = (0..3).find(|&axis| lhs[axis] != rhs[axis]).unwrap_or(0);
+    (0..3)
+        .filter(move |&other| other != axis)
+        .flat_map(|other| [-2, 2].map(|step| (other, step)))
+        .map(move |(other, step)| {
+            let (mut lhs, mut rhs) = (lhs, rhs);
+            lhs[other] += step;
+            rhs[other] += step;
+            (lhs, rhs)
+        })
+}
+
+/// Replace the neighbor old of vert with new.
+fn relink(neighbors: &mut Neighbors, vert: V3d, old: V3d, new: V3d) {
+    if let Some(slot) = neighbors
+        .get_mut(&vert)
+        .and_then(|verts| verts.iter_mut().find(|slot| **slot == old))
+    {
+        *slot = new;
+    }
+}
+
+/// Read each cycle off the neighbors once, starting from the first vertex of any subtour it contains.
+fn walk(
+    neighbors: impl Fn(V3d) -> [V3d; 2],
+    starts: impl Iterator<Item = V3d>,
+    mut cycles: Cycles,
+) -> Subtours {
+    let mut walked = vec![false; cycles.parent.len()];
+    starts
+        .enumerate()
+        .filter_map(|(id, start)| {
+            let root = cycles.find(id);
+            (!std::mem::replace(&mut walked[root], true)).then(|| {
+                let mut tour = vec![start];
+                let (mut prev, mut vert) = (start, neighbors(start)[1]);
+                while vert != start {
+                    tour.push(vert);
+                    let next = match neighbors(vert) {
+                        [lhs, rhs] if lhs == prev => rhs,
+                        [lhs, _] => lhs,
+                    };
+                    (prev, vert) = (vert, next);
+                }
+                tour
+            })
+        })
+        .collect()
+}
+
+/// Disjoint sets of the ids of the subtours joined into the same cycle.
+struct Cycles {
+    parent: Vec<usize>,
+    remaining: usize,
+}
+
+impl Cycles {
+    fn new(count: usize) -> Cycles {
+        Cycles {
+            parent: (0..count).collect(),
+            remaining: count,
+        }
+    }
+
+    fn find(&mut self, mut id: usize) -> usize {
+        while self.parent[id] != id {
+            self.parent[id] = self.parent[self.parent[id]];
+            id = self.parent[id];
+        }
+        id
+    }
+
+    fn union(&mut self, lhs: usize, rhs: usize) {
+        let (lhs, rhs) = (self.find(lhs), self.find(rhs));
+        if lhs != rhs {
+            self.parent[rhs] = lhs;
+            self.remaining -= 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use itertools::Itertools;
+
+    use super::*;
+    use crate::graph::{
+        ops::{certify_solution::Certify, ham_cycle::HamCycle, prelude::*},
+        weave::weave,
+    };
+
+    /// The edges of each subtour, closing edge included.
+    fn edges_of(subtours: &[Tour]) -> Edges {
+        subtours
+            .iter()
+            .flat_map(|subtour| subtour.iter().copied().circular_tuple_windows())
+            .collect()
+    }
+
+    /// 🩺 The cycles of the mirrored loom are a 2-factor of the discocube: decomposed from their edges and merged they are a Hamiltonian cycle.
+    #[test]
+    fn test_merge_two_factor() {
+        for n in 1..=12 {
+            let mut loom = Loom::with_capacity(n.loom_size());
+            let yarns = Yarns::color_spun(Spindle::spin_out(n));
+            n.z_color_len().into_iter().for_each(|((z, color), len)| {
+                let mut pins = loom.pin_thread_ends(z);
+                loom.extend_threads(yarns.prep(z, color, len).chop(&mut pins));
+            });
+            let warps = loom
+                .mirror_threads()
+                .iter()
+                .map(|cycle| cycle.verts().collect())
+                .collect::<Subtours>();
+            let subtours = edges_of(&warps).decompose().unwrap();
+            assert_eq!(subtours.len(), warps.len());
+            let merged = subtours.merge_subtours().unwrap();
+            assert!(HamCycle::try_from(merged, n).is_ok());
+            let merged = subtours.merge_parallel().unwrap();
+            assert!(HamCycle::try_from(merged, n).is_ok());
+        }
+    }
+
+    /// 🩺 A woven tour missing an edge is a path whose ends are adjacent, which closes back into the cycle.
+    #[test]
+    fn test_merge_damaged_tour() {
+        let woven = weave(4).unwrap();
+        let mut edges = edges_of(&[woven.to_vec()]);
+        edges.remove(&(woven[10], woven[11]));
+        let subtours = edges.decompose().unwrap();
+        assert_eq!(subtours.len(), 1);
+        assert!([woven[10], woven[11]].contains(&subtours[0][0]));
+        let merged = subtours.merge_subtours().unwrap();
+        assert_eq!(merged.certify(160, 9), SequenceID::HamCycle);
+    }
+
+    /// 🩺 Squares stacked on top of each other have bridges, squares apart don't and neither do open paths.
+    #[test]
+    fn test_merge_obstructed() {
+        let square = |z| vec![[1, 1, z], [3, 1, z], [3, 3, z], [1, 3, z]];
+        let stacked = vec![square(1), square(3), square(5)];
+        for merged in [stacked.merge_subtours(), stacked.merge_parallel()] {
+            let merged = merged.unwrap();
+            assert_eq!(merged.len(), 12);
+            assert!(merged
+                .iter()
+                .circular_tuple_windows()
+                .all(|(lhs, rhs)| lhs.is_adj_to(*rhs)));
+        }
+        let apart = vec![square(1), square(3), square(7)];
+        let Err(WeaveError::MergeObstructed(obstruction)) = apart.merge_subtours() else {
+            panic!("squares apart merged");
+        };
+        assert_eq!(obstruction.subtours.len(), 2);
+        assert_eq!(
+            apart.merge_parallel(),
+            Err(WeaveError::MergeObstructed(obstruction.clone()))
+        );
+        assert_eq!(
+            obstruction.to_string(),
+            "2 subtours of lengths [8, 4] with no bridge between any two"
+        );
+        let open = vec![square(1), vec![[1, 1, 3], [3, 1, 3], [3, 3, 3]]];
+        assert_eq!(
+            open.merge_subtours().unwrap_err().to_string(),
+            "Merge obstructed: 2 subtours of lengths [4, 3] of which 1 open"
+        );
+    }
+
+    /// 🩺 Squares far from the origin merge in parallel without tables as large as their ranks.
+    #[test]
+    fn test_merge_parallel_far_from_origin() {
+        let square = |z| vec![[1, 1, z], [3, 1, z], [3, 3, z], [1, 3, z]];
+        let far = vec![square(20001), square(20003)];
+        let merged = far.merge_parallel().unwrap();
+        assert_eq!(merged.len(), 8);
+        assert!(merged
+            .iter()
+            .circular_tuple_windows()
+            .all(|(lhs, rhs)| lhs.is_adj_to(*rhs)));
+        assert_eq!(far.merge_subtours().unwrap().len(), 8);
+    }
+
+    /// 🩺 Pairs which aren't edges and vertices with more than two edges are refused.
+    #[test]
+    fn test_decompose_invalid() {
+        let mut edges = Edges::from([([1, 1, 1], [1, 1, 3]), ([1, 1, 1], [3, 1, 1])]);
+        assert_eq!(
+            edges.decompose().unwrap(),
+            vec![vec![[1, 1, 3], [1, 1, 1], [3, 1, 1]]]
+        );
+        edges.insert(([1, 1, 1], [1, 3, 1]));
+        assert_eq!(
+            edges.decompose(),
+            Err(WeaveError::BranchingVertex([1, 1, 1]))
+        );
+        let edges = Edges::from([([1, 1, 1], [1, 1, 5])]);
+        assert_eq!(
+            edges.decompose(),
+            Err(WeaveError::InvalidEdge(([1, 1, 1], [1, 1, 5])))
+        );
+    }
+}
@@ -1,6 +1,9 @@
 use ndarray::Array2;
 use std::collections::{HashMap, HashSet, VecDeque};
 
+pub use super::strands::SpoolIndex;
+use super::{ops::splice::Span, strands::Strand};
+
 pub type BridgeEdge = Edge;
 pub type ColorIdx = u8;
 pub type Count = usize;
@@ -13,11 +16,18 @@ pub type LoomSlice<'a> = &'a [V3d];
 pub type Node = u32;
 pub type Nodes = Vec<u32>;
 pub type PinCushion = Vec<V3d>;
+#[cfg(not(feature = "i32"))]
 pub type ScalarXyz = i16;
+#[cfg(feature = "i32")]
+pub type ScalarXyz = i32;
 pub type SignedIdx = i32;
 pub type Solution = Tour;
 pub type Spindle = Vec<V2d>;
 pub type Spun = HashMap<V2d, bool>;
+pub type StrandLoom = Vec<StrandThread>;
+pub type StrandThread = VecDeque<Strand>;
+pub type Strands = Vec<Strand>;
+pub type ThreadSpan = (Count, Span);
 pub type Subtours = Vec<Tour>;
 pub type Tour = Vec<V3d>;
 pub type V2d = [ScalarXyz; 2];
@@ -1,4 +1,46 @@
-use super::ops::prelude::*;
+use super::{
+    error::WeaveError,
+    ops::{graph_info_from_n::MAX_N, ham_cycle::HamCycle, prelude::*},
+    sink::TourSink,
+    strands::{MergeStrands, MirrorStrands, StrandSequence, WeaveStrands},
+};
+
+use std::{fmt, str::FromStr};
+
+/// 🌳 How the cycles of the mirrored loom are merged into one.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum MergeStrategy {
+    /// Join each warp into the weft in turn at the bridge predicted for it, see `weave()`.
+    #[default]
+    Sequential,
+    /// Cut the weft at the bridges of all the warps at once and join the neighbouring pieces pairwise in parallel, round after round, as a reduction tree, see `Weft::reduce`.
+    TreeReduction,
+}
+
+/// impl Display to print out MergeStrategy as it is parsed.
+impl fmt::Display for MergeStrategy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MergeStrategy::Sequential => write!(f, "sequential"),
+            MergeStrategy::TreeReduction => write!(f, "tree"),
+        }
+    }
+}
+
+/// Parse `sequential` or `tree`, e.g. from the command line.
+impl FromStr for MergeStrategy {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<MergeStrategy, String> {
+        match name {
+            "sequential" => Ok(MergeStrategy::Sequential),
+            "tree" => Ok(MergeStrategy::TreeReduction),
+            _ => Err(format!(
+                "unknown merge strategy {name}, expected sequential or tree"
+            )),
+        }
+    }
+}
 
 /// 🪡 Weave a Hamiltonian cycle by building chains level by level bottom up halfway up the graph. Mirror chains to form cycles for subsequent joining of weft with each warp in the loom until only the weft remains. A construction algorithm for describing the discocube, a techno-upgrade of the discoball 🪩 that's still a discoball but reflects the intertwined complexity of our algorithmically connected world. A disco ball fits well in a 1920's ballroom, but what fits well in a techno hall? \
 ///
@@ -12,8 +54,8 @@ use super::ops::prelude::*;
 /// `🧮 warps`: Threads which are built horizontally upwards level by level until half the graph has been built. \
 /// ---\
 ///
-///```
-///pub fn weave(n: usize) -> Solution {
+///```ignore
+///pub fn weave(n: usize) -> Result<HamCycle, WeaveError> {
 ///
 ///    //  Create a loom instance with a specific size from InfoN.
 ///    let mut loom = Loom::with_capacity(n.loom_size());
@@ -37,35 +79,37 @@ use super::ops::prelude::*;
 ///    // ◡ + ◠ = ◯
 ///    // Loom before:  ◡ ◡ ◡ ◡ ◡ ◡ ◡ ◡ + ◠ ◠ ◠ ◠ ◠ ◠ ◠ ◠
 ///    // Loom after:   ◯ ◯ ◯ ◯ ◯ ◯ ◯ ◯
-///    loom.mirror_threads();
-///
-///    // Split weft from the loom leaving only the warps.
+///    // Only ◡ is stored, flagged as mirrored: ◠ is read off it when iterating, so the merge runs on half the vertices.
+///    // Split weft from the mirrored loom leaving only the warps.
 ///    // solution = ((((weft + warp) + warp) + warp) + warp)
-///    let (mut weft, mut loom) = loom.prepare_merging(n);
+///    let (mut weft, mut loom) = loom.mirror_threads().prepare_cycle_merging(n);
 ///
 ///    // Iterate over each warp in the loom and incorporate into the weft.
-///    loom.iter_mut().for_each(|warp| {
-///
-///        // Get edges which are also valid bridges
-///        let warp_edges = warp.edges(weft.max_sum_z, weft.joined);
+///    // Any missing bridge or misaligned warp is returned as a WeaveError.
+///    loom.iter_mut().try_for_each(|warp| {
 ///
 ///        // A bridge is an edge that shares an adjacent edge with another sequence and used
 ///        // as a bridge to join two cycles.
-///        // Get the bridge edge on the weft to join to with the bridge edge of the warp.
-///        let weft_bridge = weft.edges().bridge(&warp_edges);
+///        // Both bridges lie at the predicted elevation so they are computed from the state of the weft.
+///        let (weft_bridge, warp_bridge) = weft.bridges();
+///
+///        // Debug builds check the computed bridges against the intersection of the edges of weft and warp.
+///        #[cfg(debug_assertions)]
+///        assert_eq!(weft.search_bridges(warp), Ok((weft_bridge, warp_bridge)));
 ///
 ///        // Align/Rotate weft so the ends match weft's bridge.
-///        weft.align_to(weft_bridge);
+///        weft.align_to(weft_bridge)?;
 ///
-///        // Align/Rotate weft so the ends match warp's bridge.
-///        warp.align_to(warp_edges.bridge(&weft_bridge));
+///        // Align/Rotate warp so the ends match warp's bridge. Only the spans of the warp move.
+///        warp.align_to(warp_bridge)?;
 ///
 ///        // Now that both are aligned weft joins with warp by appending.
 ///        weft.join(warp);
-///    });
+///        Ok(())
+///    })?;
 ///
-///    // After weaving there's is only the weft. Retrieve the finished weave.
-///    weft.get_woven()
+///    // After weaving there's is only the weft. Retrieve the finished weave, a Hamiltonian cycle by construction: the reflected halves are expanded here.
+///    Ok(HamCycle::woven(weft.get_woven(), n))
 ///
 ///    // Output results to a csv file:
 ///    weft.export_csv()
@@ -75,11 +119,11 @@ use super::ops::prelude::*;
 ///
 /// Spin and color yarn. From the bottom-up for each level: cut the yarn incorporate into the level using pins if necessary to affix to the previous threads. Prepare pins for the next level. When we've reached the top, reflect the loom. Separate the loom into a main weft and warps. Incorporate the weft into the warps. Return solution.\
 /// For each level, pin each end of each thread in the loom. Get the requested color and cut yarn using pins.
-/// Merge subcycles by first calculating the bridge between warp's and weft's edges: Align each sequence to their respective edge such that the two sequences can be placed next to another. Append the warp to the weaver's weft. Continue to incorporate warps into the weft until only the weft remains.\
+/// Merge subcycles by first calculating the bridge between warp's and weft's edges from the elevation of the next merge: Align each sequence to their respective edge such that the two sequences can be placed next to another. Append the warp to the weaver's weft. Continue to incorporate warps into the weft until only the weft remains.\
 ///---\
 /// ---\
 /// I've placed most of the implementations in the ops.rs file to avoid cluttering the structure of the actual algorithm. The ops.rs file is structured so that it follows the order of the weave algorithm, where each function is encapsulated in a separate module and imported using `prelude::*`. Here is a list of the modules with the corresponding call to that module in weave.\
-///```
+///```text
 ///graph_info_from_n::InfoN ────────────➤  n.loom_size()
 ///                                        n.spool_size()
 ///                                        n.zrow_color_len()
@@ -97,45 +141,152 @@ use super::ops::prelude::*;
 ///mirror_loom::MirrorLoomThreads ──────➤  loom.mirror_threads()
 ///
 ///merge_cycles::* ─────────────────────➤  loom.prepare_cycle_merging()
-///                                        warp.edges()
-///                                        weft.edges().bridge(&warp_edges)
-///                                        weft.data.align_to(weft_bridge)
-///                                        warp.align_to(warp_edges.bridge(&weft_bridge))
+///                                        weft.bridges()
+///                                        weft.align_to(weft_bridge)
+///                                        warp.align_to(warp_bridge)
 ///                                        weft.join(warp)
 ///
 ///```
 ///
 ///
-pub fn weave(n: usize) -> Solution {
+pub fn weave(n: usize) -> Result<HamCycle, WeaveError> {
+    weave_with(n, MergeStrategy::Sequential)
+}
+
+/// 🌳 Weave the same way as `weave()` up to the mirrored loom and merge its cycles with the strategy chosen.\
+/// `MergeStrategy::TreeReduction` joins the warps on all threads instead of one by one, into the same weave.
+///```ignore
+///let woven = weave_with(1000, MergeStrategy::TreeReduction)?;
+///```
+pub fn weave_with(n: usize, strategy: MergeStrategy) -> Result<HamCycle, WeaveError> {
+    weave_probed(n, strategy, &mut ())
+}
+
+/// 🔬 Looks at each phase of `weave_probed()` as it runs: its name, the level it's run for, if any, and the phase itself.\
+/// The default runs the phase and nothing else, so `()` weaves as if there were no probe, see `profile::weave_profiled()` for one that times them.
+pub(crate) trait Probe {
+    fn phase<T>(
+        &mut self,
+        _phase: &'static str,
+        _z: Option<ScalarXyz>,
+        run: impl FnOnce() -> T,
+    ) -> T {
+        run()
+    }
+}
+
+impl Probe for () {}
+
+/// The weave of `weave_with()`, every phase of it run through the probe.
+pub(crate) fn weave_probed<P: Probe>(
+    n: usize,
+    strategy: MergeStrategy,
+    probe: &mut P,
+) -> Result<HamCycle, WeaveError> {
+    check_n(n)?;
     let mut loom = Loom::with_capacity(n.loom_size());
-    let yarns = Yarns::color_spun(Spindle::spin_out(n));
-    n.z_color_len().into_iter().for_each(|((z, color), len)| {
-        let mut pins = loom.pin_thread_ends(z);
-        loom.extend_threads(yarns.prep(z, color, len).chop(&mut pins));
-    });
-    loom.mirror_threads();
-    let (mut weft, mut loom) = loom.prepare_cycle_merging(n);
-    loom.iter_mut().for_each(|warp| {
-        let warp_edges = warp.edges(weft.joined);
-        let weft_bridge = weft.edges().bridge(&warp_edges);
-        weft.data.align_to(weft_bridge);
-        warp.align_to(warp_edges.bridge(&weft_bridge));
-        weft.join(warp);
+    let spool = probe.phase("spin_out", None, || Spindle::spin_out(n));
+    let yarns = probe.phase("color_spun", None, || Yarns::color_spun(spool));
+    for ((z, color), len) in n.z_color_len() {
+        let mut pins = probe.phase("pin_thread_ends", Some(z), || loom.pin_thread_ends(z));
+        let warp = probe.phase("prep", Some(z), || yarns.prep(z, color, len));
+        let warps = probe.phase("chop", Some(z), || warp.chop(&mut pins));
+        probe.phase("extend_threads", Some(z), || loom.extend_threads(warps));
+    }
+    let threads = probe.phase("mirror_threads", None, || loom.mirror_threads());
+    if strategy == MergeStrategy::TreeReduction {
+        let (mut weft, pieces) = probe.phase("prepare_cycle_merging", None, || {
+            threads.prepare_cycle_reduction(n)
+        })?;
+        probe.phase("merge", None, || weft.reduce(pieces));
+        return Ok(HamCycle::woven(weft.get_woven(), n));
+    }
+    let (mut weft, mut loom) = probe.phase("prepare_cycle_merging", None, || {
+        threads.prepare_cycle_merging(n)
     });
-    weft.get_woven()
+    probe.phase("merge", None, || {
+        loom.iter_mut()
+            .try_for_each(|warp| -> Result<(), WeaveError> {
+                let (weft_bridge, warp_bridge) = weft.bridges();
+                #[cfg(debug_assertions)]
+                assert_eq!(weft.search_bridges(warp), Ok((weft_bridge, warp_bridge)));
+                weft.align_to(weft_bridge)?;
+                warp.align_to(warp_bridge)?;
+                weft.join(warp);
+                Ok(())
+            })
+    })?;
+    Ok(HamCycle::woven(weft.get_woven(), n))
+}
+
+/// 🚰 Weave the same Hamiltonian cycle as `weave()` but stream it vertex by vertex into a sink instead of returning it.\
+///
+///---\
+/// `🧭 n`: NInfo instance used to get parameters from the graph.\
+/// `🕳️ sink`: Receives each vertex of the finished weave in order: a `Solution`, a `CsvSink`, a `BinarySink` or a `CallbackSink`.\
+/// ---\
+///
+/// Every level of the loom is a slice of the same spun yarn so the threads are built as strands: ranges on the yarn, see `strands::Strand`. Mirroring a thread only mirrors its strands and the warps are joined into the weft by relinking the ends of strands. The vertices are read off the yarn only when the finished weave is written to the sink.\
+/// The full tour is never materialized: memory is proportional to the spool size `2n(n + 1)` instead of to the order `4n(n + 1)(n + 2) / 3`.\
+///```ignore
+///let mut sink = CsvSink::create("solution_1817.csv")?;
+///weave_into(1817, &mut sink)?;
+///```
+pub fn weave_into<W: TourSink>(n: usize, sink: &mut W) -> Result<(), WeaveError> {
+    check_n(n)?;
+    let (yarns, index, loom) = StrandLoom::weave_strands(n)?;
+    let weft = loom.mirror_strands().merge_strands(n, &index)?;
+    weft.verts(&yarns).try_for_each(|vert| sink.put(vert))?;
+    sink.finish()
 }
 
-///! 🩺 TEST
-///!
-///!
-///!
-///!
-///!
-///! 🩺 Test to check that the results from the first 50 orders are Hamiltonian cycles.
+/// 🔢 Weave the same Hamiltonian cycle as `weave()` as the ranks of its vertices in the (l1norm, x, y, z) order of `make_vertices`, see `translate::rank`.\
+/// Each vertex is ranked as it is streamed out of the weave, so neither the tour of `V3d` is built nor sorted: `Nodes` take 4 bytes per vertex instead of 6.
+/// Discocubes with more than `u32::MAX` vertices, from n = 1476 on, need `Vec<u64>`: `Nodes` return `WeaveError::ArithmeticOverflow` up front instead of wrapping.
+///```ignore
+///let nodes: Nodes = weave_nodes(100)?;
+///let nodes: Vec<u64> = weave_nodes(1817)?;
+///```
+pub fn weave_nodes<N>(n: usize) -> Result<Vec<N>, WeaveError>
+where
+    N: TryFrom<u64>,
+    Vec<N>: TourSink,
+{
+    check_n(n)?;
+    let order = n.get_order_from_n();
+    N::try_from(order as u64 - 1).map_err(|_| WeaveError::ArithmeticOverflow(n))?;
+    let mut nodes = Vec::with_capacity(order);
+    weave_into(n, &mut nodes)?;
+    Ok(nodes)
+}
+
+/// Check that there's a discocube for n and that its coordinates fit in `ScalarXyz`.
+pub(crate) fn check_n(n: usize) -> Result<(), WeaveError> {
+    match n {
+        0 => Err(WeaveError::InvalidN(n)),
+        n if n > MAX_N => Err(WeaveError::CoordinateOverflow { n, max_n: MAX_N }),
+        _ => Ok(()),
+    }
+}
+
+// 🩺 TEST
+//
+//
+//
+//
+//
+// 🩺 Test to check that the results from the first 50 orders are Hamiltonian cycles.
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::graph::ops::certify_solution::{Certify, SequenceID};
+    use crate::graph::{
+        ops::{
+            certify_solution::{Certify, SequenceID},
+            serialize_csv::SerializeToCsv,
+            translate::VectorsToNodes,
+        },
+        sink::{CallbackSink, CsvSink},
+    };
 
     /// 🩺 Run weave on the first 50 instances and test if the results are Hamiltonian cycles.
     #[test]
@@ -143,9 +294,79 @@ mod tests {
         for n in 1..=50 {
             let order = n.get_order_from_n();
             let max_absumv = n.get_max_absumv();
-            let solution = weave(n);
+            let solution = weave(n).unwrap();
             let seq_id = solution.certify(order, max_absumv);
             assert_eq!(seq_id, SequenceID::HamCycle);
         }
     }
+
+    /// 🩺 Merging the cycles as a reduction tree gives the same Hamiltonian cycles as joining them one by one.
+    #[test]
+    fn test_weave_tree_reduction() {
+        for n in 1..=50 {
+            let woven = weave_with(n, MergeStrategy::TreeReduction).unwrap();
+            let seq_id = woven.certify(n.get_order_from_n(), n.get_max_absumv());
+            assert_eq!(seq_id, SequenceID::HamCycle);
+            assert_eq!(woven, weave(n).unwrap());
+        }
+        for strategy in [MergeStrategy::Sequential, MergeStrategy::TreeReduction] {
+            assert_eq!(strategy.to_string().parse(), Ok(strategy));
+        }
+        assert!("binary".parse::<MergeStrategy>().is_err());
+    }
+
+    /// 🩺 Streaming into a solution gives the exact same weave, and the csv sink writes the same file as `serialize_to_csv`.
+    #[test]
+    fn test_weave_into() {
+        for n in 1..=50 {
+            let mut streamed = Solution::new();
+            weave_into(n, &mut streamed).unwrap();
+            assert_eq!(streamed, *weave(n).unwrap());
+        }
+        let mut csv = CsvSink::new(Vec::new());
+        weave_into(3, &mut csv).unwrap();
+        let written = csv.into_inner().unwrap();
+        weave(3)
+            .unwrap()
+            .serialize_to_csv("test_weave_into.csv")
+            .unwrap();
+        assert_eq!(written, std::fs::read("test_weave_into.csv").unwrap());
+        std::fs::remove_file("test_weave_into.csv").unwrap();
+        let mut count = 0;
+        weave_into(4, &mut CallbackSink(|_| count += 1)).unwrap();
+        assert_eq!(count, 4.get_order_from_n());
+    }
+
+    /// 🩺 Weaving into nodes gives the ranks of the vertices of the weave, in both widths.
+    #[test]
+    fn test_weave_nodes() {
+        for n in 1..=30 {
+            let nodes: Nodes = weave_nodes(n).unwrap();
+            assert_eq!(nodes, weave(n).unwrap().to_nodes().unwrap());
+            let wide: Vec<u64> = weave_nodes(n).unwrap();
+            assert!(wide
+                .iter()
+                .zip(&nodes)
+                .all(|(&lhs, &rhs)| lhs == rhs as u64));
+        }
+        assert_eq!(weave_nodes::<Node>(0), Err(WeaveError::InvalidN(0)));
+    }
+
+    /// 🩺 n = 0 has no discocube and is rejected instead of underflowing.
+    #[test]
+    fn test_weave_invalid_n() {
+        assert_eq!(weave(0), Err(WeaveError::InvalidN(0)));
+    }
+
+    /// 🩺 n whose coordinates don't fit in ScalarXyz is rejected up front instead of wrapping.
+    #[test]
+    fn test_weave_coordinate_overflow() {
+        assert_eq!(
+            weave(MAX_N + 1),
+            Err(WeaveError::CoordinateOverflow {
+                n: MAX_N + 1,
+                max_n: MAX_N
+            })
+        );
+    }
 }
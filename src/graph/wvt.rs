@@ -0,0 +1,474 @@
+use std::{
+    fs::File,
+    io::{BufReader, BufWriter, Read, Seek, SeekFrom, Write},
+};
+
+use super::{
+    error::WeaveError,
+    ops::{graph_info_from_n::CheckedInfoN, ham_cycle::HamCycle},
+    sink::TourSink,
+    types::*,
+};
+
+/// Identifies a `.wvt` file.
+pub const WVT_MAGIC: [u8; 4] = *b"WVT\0";
+/// Version of the format written by `WvtSink`.
+pub const WVT_VERSION: u32 = 1;
+/// Size of the header in bytes: magic, version, n, order, start vertex and checksum, little-endian.
+pub const WVT_HEADER_LEN: usize = 44;
+
+/// The six steps between adjacent vertices of a discocube: 2 along one axis. The index of a step is its code: X, x, Y, y, Z, z.
+const STEPS: [V3d; 6] = [
+    [2, 0, 0],
+    [-2, 0, 0],
+    [0, 2, 0],
+    [0, -2, 0],
+    [0, 0, 2],
+    [0, 0, -2],
+];
+
+/// Bits per direction code.
+const CODE_BITS: u32 = 3;
+/// Vertices allocated up front by `WvtReader::into_tour`: the header can claim any order, so the tour only grows past this as it's read.
+const PREALLOCATED: usize = 1 << 20;
+
+/// 🏷️ What the header of a `.wvt` file says about the tour that follows it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct WvtHeader {
+    pub version: u32,
+    /// n of the discocube the tour was woven on.
+    pub n: u64,
+    /// Number of vertices in the tour, one more than the number of direction codes.
+    pub order: u64,
+    /// First vertex of the tour, as `i32` whatever the width of `ScalarXyz`.
+    pub start: [i32; 3],
+    /// FNV-1a hash of the packed direction codes followed by the fields of the header before the checksum.
+    pub checksum: u64,
+}
+
+impl WvtHeader {
+    /// Number of bytes the direction codes are packed in.
+    pub fn packed_len(&self) -> u64 {
+        (self.order.saturating_sub(1) * CODE_BITS as u64).div_ceil(8)
+    }
+
+    fn to_bytes(self) -> [u8; WVT_HEADER_LEN] {
+        let mut bytes = [0; WVT_HEADER_LEN];
+        bytes[0..4].copy_from_slice(&WVT_MAGIC);
+        bytes[4..8].copy_from_slice(&self.version.to_le_bytes());
+        bytes[8..16].copy_from_slice(&self.n.to_le_bytes());
+        bytes[16..24].copy_from_slice(&self.order.to_le_bytes());
+        (0..3).for_each(|axis| {
+            bytes[24 + 4 * axis..28 + 4 * axis].copy_from_slice(&self.start[axis].to_le_bytes())
+        });
+        bytes[36..44].copy_from_slice(&self.checksum.to_le_bytes());
+        bytes
+    }
+
+    /// Fold the fields of the header before the checksum into the hash of the direction codes, so a damaged n, order or start fails the checksum too.
+    fn sealed(&self, mut checksum: Checksum) -> u64 {
+        self.to_bytes()[..36]
+            .iter()
+            .for_each(|&byte| checksum.update(byte));
+        checksum.0
+    }
+
+    fn read_from<R: Read>(reader: &mut R) -> Result<WvtHeader, WeaveError> {
+        let mut bytes = [0; WVT_HEADER_LEN];
+        reader.read_exact(&mut bytes)?;
+        let u32_at = |at: usize| u32::from_le_bytes(bytes[at..at + 4].try_into().unwrap());
+        let u64_at = |at: usize| u64::from_le_bytes(bytes[at..at + 8].try_into().unwrap());
+        if bytes[0..4] != WVT_MAGIC {
+            return Err(WeaveError::InvalidWvt("not a .wvt file".to_string()));
+        }
+        let header = WvtHeader {
+            version: u32_at(4),
+            n: u64_at(8),
+            order: u64_at(16),
+            start: [0, 1, 2].map(|axis| u32_at(24 + 4 * axis) as i32),
+            checksum: u64_at(36),
+        };
+        if header.version != WVT_VERSION {
+            return Err(WeaveError::InvalidWvt(format!(
+                "version {} is not supported",
+                header.version
+            )));
+        }
+        let order = usize::try_from(header.n).map(CheckedInfoN::checked_order_from_n);
+        match order {
+            Ok(Ok(order)) if order as u64 == header.order => Ok(header),
+            _ => Err(WeaveError::InvalidWvt(format!(
+                "order {} is not the order of n {}",
+                header.order, header.n
+            ))),
+        }
+    }
+}
+
+/// FNV-1a, 64 bits.
+#[derive(Clone, Copy, Debug)]
+struct Checksum(u64);
+
+impl Default for Checksum {
+    fn default() -> Checksum {
+        Checksum(0xcbf29ce484222325)
+    }
+}
+
+impl Checksum {
+    fn update(&mut self, byte: u8) {
+        self.0 = (self.0 ^ byte as u64).wrapping_mul(0x100000001b3);
+    }
+}
+
+/// 🗜️ Write the tour as a `.wvt` file: a header then the step from each vertex to the next as a 3-bit direction code.
+/// A billion vertices take 375 MB, the 8 billion of n = 1817 3 GB. The closing step back to the start isn't written.
+/// The header is written again by `finish()` once the start, the order and the checksum are known, hence `Seek`.
+pub struct WvtSink<W: Write + Seek> {
+    writer: W,
+    header: WvtHeader,
+    last: Option<V3d>,
+    bits: u64,
+    len_bits: u32,
+    checksum: Checksum,
+}
+
+impl<W: Write + Seek> WvtSink<W> {
+    /// Stream the tour woven on discocube n into `writer`. Wrap files in a `BufWriter`.
+    pub fn new(mut writer: W, n: usize) -> Result<WvtSink<W>, WeaveError> {
+        let header = WvtHeader {
+            version: WVT_VERSION,
+            n: n as u64,
+            order: 0,
+            start: [0; 3],
+            checksum: Checksum::default().0,
+        };
+        writer.write_all(&header.to_bytes())?;
+        Ok(WvtSink {
+            writer,
+            header,
+            last: None,
+            bits: 0,
+            len_bits: 0,
+            checksum: Checksum::default(),
+        })
+    }
+
+    /// Recover the writer once the tour has been streamed.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+
+    fn write_byte(&mut self, byte: u8) -> Result<(), WeaveError> {
+        self.checksum.update(byte);
+        Ok(self.writer.write_all(&[byte])?)
+    }
+}
+
+impl WvtSink<BufWriter<File>> {
+    /// Stream the tour woven on discocube n into a new file at `file_path`.
+    pub fn create(file_path: &str, n: usize) -> Result<WvtSink<BufWriter<File>>, WeaveError> {
+        WvtSink::new(BufWriter::new(File::create(file_path)?), n)
+    }
+}
+
+impl<W: Write + Seek> TourSink for WvtSink<W> {
+    /// Two vertices in a row which aren't adjacent are an `InvalidEdge`: there's no code for their step.
+    fn put(&mut self, vert: V3d) -> Result<(), WeaveError> {
+        match self.last {
+            None => self.header.start = vert.map(i32::from),
+            Some(last) => {
+                // Wide enough that the step between any two vertices is exact, even from one end of the coordinates to the other.
+                let step = [0, 1, 2].map(|axis| i64::from(vert[axis]) - i64::from(last[axis]));
+                let code = STEPS
+                    .iter()
+                    .position(|unit| unit.map(i64::from) == step)
+                    .ok_or(WeaveError::InvalidEdge((last, vert)))?;
+                self.bits |= (code as u64) << self.len_bits;
+                self.len_bits += CODE_BITS;
+                while self.len_bits >= 8 {
+                    self.write_byte(self.bits as u8)?;
+                    self.bits >>= 8;
+                    self.len_bits -= 8;
+                }
+            }
+        }
+        self.last = Some(vert);
+        self.header.order += 1;
+        Ok(())
+    }
+
+    fn finish(&mut self) -> Result<(), WeaveError> {
+        if self.len_bits > 0 {
+            self.write_byte(self.bits as u8)?;
+            (self.bits, self.len_bits) = (0, 0);
+        }
+        self.header.checksum = self.header.sealed(self.checksum);
+        self.writer.seek(SeekFrom::Start(0))?;
+        self.writer.write_all(&self.header.to_bytes())?;
+        self.writer.seek(SeekFrom::End(0))?;
+        Ok(self.writer.flush()?)
+    }
+}
+
+/// 📖 Read a `.wvt` file back vertex by vertex without loading it. The checksum is checked once the last vertex has been read:
+/// a mismatch is the last item of the iteration.
+pub struct WvtReader<R: Read> {
+    reader: R,
+    header: WvtHeader,
+    last: Option<V3d>,
+    read: u64,
+    bits: u64,
+    len_bits: u32,
+    checksum: Checksum,
+    done: bool,
+}
+
+impl<R: Read> WvtReader<R> {
+    /// Read the header from `reader`, the vertices follow as the reader is iterated. Wrap files in a `BufReader`.
+    pub fn new(mut reader: R) -> Result<WvtReader<R>, WeaveError> {
+        let header = WvtHeader::read_from(&mut reader)?;
+        Ok(WvtReader {
+            reader,
+            header,
+            last: None,
+            read: 0,
+            bits: 0,
+            len_bits: 0,
+            checksum: Checksum::default(),
+            done: false,
+        })
+    }
+
+    /// The header of the file.
+    pub fn header(&self) -> &WvtHeader {
+        &self.header
+    }
+
+    /// Read the whole tour.
+    pub fn into_tour(self) -> Result<Tour, WeaveError> {
+        let mut tour = Tour::with_capacity((self.header.order as usize).min(PREALLOCATED));
+        for vert in self {
+            tour.push(vert?);
+        }
+        Ok(tour)
+    }
+
+    /// Verify on read: read the whole tour and certify it as a Hamiltonian cycle of the discocube n of the header with `Certify`.
+    /// To certify without loading the tour see `certify_wvt()`.
+    pub fn verified(self) -> Result<HamCycle, WeaveError> {
+        let n = self.header.n as usize;
+        HamCycle::try_from(self.into_tour()?, n)
+    }
+
+    fn next_vert(&mut self) -> Result<V3d, WeaveError> {
+        let Some(last) = self.last else {
+            let start = self.header.start.map(ScalarXyz::try_from);
+            return match start {
+                [Ok(x), Ok(y), Ok(z)] => Ok([x, y, z]),
+                _ => Err(WeaveError::InvalidWvt(format!(
+                    "start {:?} doesn't fit the coordinates",
+                    self.header.start
+                ))),
+            };
+        };
+        while self.len_bits < CODE_BITS {
+            let mut byte = [0];
+            self.reader.read_exact(&mut byte)?;
+            self.checksum.update(byte[0]);
+            self.bits |= (byte[0] as u64) << self.len_bits;
+            self.len_bits += 8;
+        }
+        let code = (self.bits & 0b111) as usize;
+        (self.bits, self.len_bits) = (self.bits >> CODE_BITS, self.len_bits - CODE_BITS);
+        let step = STEPS.get(code).ok_or(WeaveError::InvalidWvt(format!(
+            "invalid direction code {code} at vertex {}",
+            self.read
+        )))?;
+        match [0, 1, 2].map(|axis| last[axis].checked_add(step[axis])) {
+            [Some(x), Some(y), Some(z)] => Ok([x, y, z]),
+            _ => Err(WeaveError::InvalidWvt(format!(
+                "step from {last:?} at vertex {} leaves the coordinates",
+                self.read
+            ))),
+        }
+    }
+}
+
+impl WvtReader<BufReader<File>> {
+    /// Read the `.wvt` file at `file_path`.
+    pub fn open(file_path: &str) -> Result<WvtReader<BufReader<File>>, WeaveError> {
+        WvtReader::new(BufReader::new(File::open(file_path)?))
+    }
+}
+
+impl<R: Read> Iterator for WvtReader<R> {
+    type Item = Result<V3d, WeaveError>;
+
+    fn next(&mut self) -> Option<Result<V3d, WeaveError>> {
+        if self.done {
+            return None;
+        }
+        if self.read == self.header.order {
+            self.done = true;
+            let found = self.header.sealed(self.checksum);
+            return (found != self.header.checksum).then_some(Err(WeaveError::ChecksumMismatch {
+                expected: self.header.checksum,
+                found,
+            }));
+        }
+        let vert = self.next_vert();
+        match vert {
+            Ok(vert) => {
+                self.last = Some(vert);
+                self.read += 1;
+            }
+            Err(_) => self.done = true,
+        }
+        Some(vert)
+    }
+}
+
+/// Save a Hamiltonian cycle as a `.wvt` file.
+pub trait SerializeToWvt {
+    /// Save the tour to `file_path` as a `.wvt` file, see `WvtSink`.
+    /// ```ignore
+    /// let solution = weave(100)?;
+    /// solution.serialize_to_wvt("documents/solutions/wvt/solution_100.wvt")
+    /// ```
+    fn serialize_to_wvt(&self, file_path: &str) -> Result<(), WeaveError>;
+}
+
+impl SerializeToWvt for HamCycle {
+    fn serialize_to_wvt(&self, file_path: &str) -> Result<(), WeaveError> {
+        let mut sink = WvtSink::create(file_path, self.n())?;
+        self.iter().try_for_each(|&vert| sink.put(vert))?;
+        sink.finish()
+    }
+}
+
+// 🩺 TEST
+//
+// 🩺 Test that tours written as `.wvt` read back the same and that damaged files are caught.
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+    use crate::graph::{ops::graph_info_from_n::InfoN, weave::weave};
+
+    /// Write the weave of n to a `.wvt` in memory.
+    fn written(n: usize) -> Vec<u8> {
+        let mut sink = WvtSink::new(Cursor::new(Vec::new()), n).unwrap();
+        weave(n)
+            .unwrap()
+            .iter()
+            .try_for_each(|&vert| sink.put(vert))
+            .unwrap();
+        sink.finish().unwrap();
+        sink.into_inner().into_inner()
+    }
+
+    #[test]
+    /// 3 bits a step after the header, read back to the same tour.
+    fn test_write_read() {
+        for n in 1..=10 {
+            let bytes = written(n);
+            let reader = WvtReader::new(bytes.as_slice()).unwrap();
+            let order = n.get_order_from_n() as u64;
+            assert_eq!(
+                (reader.header().n, reader.header().order),
+                (n as u64, order)
+            );
+            assert_eq!(reader.header().packed_len(), (3 * (order - 1)).div_ceil(8));
+            assert_eq!(
+                bytes.len() as u64,
+                WVT_HEADER_LEN as u64 + reader.header().packed_len()
+            );
+            assert_eq!(reader.into_tour().unwrap(), *weave(n).unwrap());
+            let verified = WvtReader::new(bytes.as_slice()).unwrap().verified();
+            assert_eq!(verified, Ok(weave(n).unwrap()));
+        }
+    }
+
+    #[test]
+    /// A changed code fails the checksum, a bad magic, a cut file or a tour that isn't a cycle are errors.
+    fn test_damaged() {
+        let mut bytes = written(3);
+        bytes[WVT_HEADER_LEN + 5] ^= 0b1001;
+        let read = WvtReader::new(bytes.as_slice())
+            .unwrap()
+            .collect::<Vec<_>>();
+        assert!(read.iter().any(|vert| matches!(
+            vert,
+            Err(WeaveError::ChecksumMismatch { .. }) | Err(WeaveError::InvalidWvt(_))
+        )));
+        let bytes = written(3);
+        assert!(WvtReader::new(&bytes[1..]).is_err());
+        let cut = WvtReader::new(&bytes[..bytes.len() - 2]).unwrap();
+        assert!(matches!(cut.into_tour(), Err(WeaveError::Io(_))));
+        let mut sink = WvtSink::new(Cursor::new(Vec::new()), 1).unwrap();
+        sink.put([1, 1, 1]).unwrap();
+        assert_eq!(
+            sink.put([1, -1, -1]),
+            Err(WeaveError::InvalidEdge(([1, 1, 1], [1, -1, -1])))
+        );
+        assert_eq!(sink.put([1, 1, -1]), Ok(()));
+        let written_tour = |tour: &[V3d], n: usize| {
+            let mut sink = WvtSink::new(Cursor::new(Vec::new()), n).unwrap();
+            tour.iter().try_for_each(|&vert| sink.put(vert)).unwrap();
+            sink.finish().unwrap();
+            sink.into_inner().into_inner()
+        };
+        let back_and_forth = [[1, 1, -1], [1, 1, 1]].repeat(4);
+        let bytes = written_tour(&back_and_forth, 1);
+        assert!(matches!(
+            WvtReader::new(bytes.as_slice()).unwrap().verified(),
+            Err(WeaveError::NotAHamCycle(_))
+        ));
+        // A header whose order isn't the order of its n, e.g. a huge order, is refused before anything is allocated.
+        let short = written_tour(&back_and_forth[..3], 1);
+        assert!(matches!(
+            WvtReader::new(short.as_slice()),
+            Err(WeaveError::InvalidWvt(_))
+        ));
+        let mut huge = written_tour(&back_and_forth, 1);
+        huge[16..24].copy_from_slice(&u64::MAX.to_le_bytes());
+        assert!(matches!(
+            WvtReader::new(huge.as_slice()),
+            Err(WeaveError::InvalidWvt(_))
+        ));
+        // Steps walking off the coordinates are invalid instead of wrapping.
+        let mut edge = written_tour(&back_and_forth, 1);
+        let [max, _, _] = [ScalarXyz::MAX; 3].map(i32::from);
+        edge[24..28].copy_from_slice(&max.to_le_bytes());
+        edge[WVT_HEADER_LEN] = 0;
+        let read = WvtReader::new(edge.as_slice()).unwrap().into_tour();
+        assert!(matches!(read, Err(WeaveError::InvalidWvt(_))));
+        // The header is covered by the checksum: a moved start reads to the end, then fails it.
+        let mut moved = written(3);
+        moved[24..28].copy_from_slice(&3i32.to_le_bytes());
+        let read = WvtReader::new(moved.as_slice()).unwrap().into_tour();
+        assert!(matches!(read, Err(WeaveError::ChecksumMismatch { .. })));
+    }
+
+    #[test]
+    /// Vertices at opposite ends of the coordinates are no step at all, rather than a step wrapped around to a code.
+    fn test_extremes() {
+        let (max, min) = (ScalarXyz::MAX, -ScalarXyz::MAX);
+        let mut sink = WvtSink::new(Cursor::new(Vec::new()), 1).unwrap();
+        sink.put([max, 1, 1]).unwrap();
+        assert_eq!(
+            sink.put([min, 1, 1]),
+            Err(WeaveError::InvalidEdge(([max, 1, 1], [min, 1, 1])))
+        );
+        assert_eq!(sink.put([max - 2, 1, 1]), Ok(()));
+        assert_eq!(
+            sink.put([max - 2, min, 1]),
+            Err(WeaveError::InvalidEdge((
+                [max - 2, 1, 1],
+                [max - 2, min, 1]
+            )))
+        );
+    }
+}
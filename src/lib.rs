@@ -0,0 +1,43 @@
+//! 🧶 Weave a Hamiltonian cycle on any discocube, the 3-dimensional grid graph of the points contained within an octahedron.
+//!
+//! The items re-exported at the root of this crate are the public surface:
+//!
+//! ```
+//! use weaver::{weave, Certify, InfoN, SequenceID};
+//!
+//! let n = 10;
+//! let solution = weave(n)?;
+//! assert_eq!(
+//!     solution.certify(n.get_order_from_n(), n.get_max_absumv()),
+//!     SequenceID::HamCycle
+//! );
+//! # Ok::<(), weaver::WeaveError>(())
+//! ```
+//!
+//! The building blocks of the algorithm live under `graph` and follow the order of the weave, see `graph::weave`. They are not part of the stable surface and may change with the algorithm.
+
+#[doc(hidden)]
+pub mod graph;
+
+pub use graph::{
+    certify_stream::{certify_csv, certify_stream, certify_wvt, StreamCertifier},
+    error::WeaveError,
+    extras::iters::{is_uon, nearest_uon, uon_range, Uon},
+    import::{verify_file, ReadTour},
+    ops::{
+        certify_solution::{Adjacency, Certify, CertifyReport, Csr, Discocube, SequenceID},
+        grade_solution::Grade,
+        graph_info_from_n::{CheckedInfoN, InfoN, MAX_N},
+        ham_cycle::HamCycle,
+        serialize_chars::{Decode, Encode},
+        serialize_csv::SerializeToCsv,
+        translate::{rank, unrank},
+    },
+    profile::{weave_profiled, CountingAlloc, PhaseProfile, WeaveProfile},
+    query::{tour_at, tour_position, TourIter, TourPosition, WovenTour},
+    sink::{BinarySink, CallbackSink, CsvSink, TourSink},
+    subtours::{Decompose, MergeSubtours, Obstruction},
+    types::{Count, Edge, Edges, Node, Nodes, ScalarXyz, Solution, Subtours, Tour, V2d, V3d},
+    weave::{weave, weave_into, weave_nodes, weave_with, MergeStrategy},
+    wvt::{SerializeToWvt, WvtHeader, WvtReader, WvtSink, WVT_HEADER_LEN},
+};
@@ -1,11 +1,19 @@
 /// see n_order.txt for a list of n and the corresponding order:
 /// n: 100 = 1_373_600 vertices
 /// ```
-/// cargo run --release [N] [N_UPPER_INCLUSIVE] [STEPS]
-/// cargo run --release 1 100 2
-/// cargo run --release [N] [N_UPPER_INCLUSIVE] [STEPS]
-/// cargo run --release 1 100 2
+/// cargo run --release -- solve --n 100
+/// cargo run --release -- solve --range 1..100 --step 2 --repeats 1 --merge tree
+/// cargo run --release -- bench --range 1..100 --repeats 10 --format json --out bench.json
+/// cargo run --release -- bench --range 1..100 --repeats 10 --baseline bench.json --threshold 5
+/// cargo run --release -- verify solution_100.csv
+/// cargo run --release -- export --n 100 --format wvt --out solution_100.wvt
+/// cargo run --release -- encode --n 10 --out solution_10.txt
+/// cargo run --release -- decode solution_10.txt --format json --out solution_10.json
+/// cargo run --release -- grade --n 100
+/// cargo run --release -- info --order 1373600
+/// cargo run --release --features profile -- profile --n 500 --format json
 /// ```
+/// see `cli::USAGE` for every command and flag, wrong arguments exit with 2 and a failed weave or certification with 1.
 /// builds binary under hamcycle/target/release/hamcycle
 /// runs binary: ./hamcycle/target/release/hamcycle
 /// starts with the first order in the sequence with 32 vertices,
@@ -41,79 +49,329 @@
 /////////////////////////////////////////////////////////////////////////////
 extern crate rayon;
 
-use std::{
-    env,
-    time::{Duration, Instant},
-};
+mod bench;
+mod cli;
 
-pub mod graph;
+use std::{env, fmt, fs::File, io::BufWriter, process};
 
-use graph::{
-    extras::debug::get_current_date_time,
-    ops::{
-        certify_solution::{Certify, SequenceID},
-        graph_info_from_n::*,
-    },
-    types::*,
-    weave,
+use bench::{bench_n, read_baseline, regressions, render, write_records, BenchRecord};
+use cli::{BenchFormat, Command, Format, ProfileFormat, Runs, USAGE};
+use weaver::{
+    graph::extras::debug::get_current_date_time, verify_file, weave, weave_into, weave_profiled,
+    weave_with, BinarySink, CheckedInfoN, CsvSink, Decode, Encode, Grade, HamCycle, InfoN,
+    MergeStrategy, SequenceID, TourSink, WeaveError, WvtHeader, WvtSink, WVT_HEADER_LEN,
 };
 
-/// Grab arguments from the cli and run `find_solution()`
-pub fn main() -> Result<(), &'static str> {
+/// Count allocations for `profile` to report the memory of each phase, only when asked for as it slows down every allocation.
+#[cfg(feature = "profile")]
+#[global_allocator]
+static ALLOC: weaver::CountingAlloc = weaver::CountingAlloc;
+
+/// Parse the command and run it. Exits with 2 if the arguments aren't understood and with 1 if the command fails.
+pub fn main() {
     std::env::set_var("RUST_BACKTRACE", "1");
+    let args: Vec<String> = env::args().skip(1).collect();
+    let command = match Command::parse(&args) {
+        Ok(command) => command,
+        Err(error) => {
+            eprintln!("{error}\n\n{USAGE}");
+            process::exit(2)
+        }
+    };
+    if let Err(error) = run(command) {
+        eprintln!("❌ {error}");
+        process::exit(1)
+    }
+}
+
+/// Why a command failed.
+enum Failure {
+    /// The weave, certification or file of a single command.
+    Weave(WeaveError),
+    /// How many n of a batch failed and how many are slower than their baseline, each already reported as it ran.
+    Batch { failed: usize, slower: usize },
+}
+
+impl From<WeaveError> for Failure {
+    fn from(error: WeaveError) -> Failure {
+        Failure::Weave(error)
+    }
+}
+
+impl From<std::io::Error> for Failure {
+    fn from(error: std::io::Error) -> Failure {
+        Failure::Weave(error.into())
+    }
+}
+
+impl fmt::Display for Failure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Failure::Weave(error) => write!(f, "{error}"),
+            Failure::Batch { failed, slower } => {
+                write!(f, "{failed} failed, {slower} slower than the baseline")
+            }
+        }
+    }
+}
+
+fn run(command: Command) -> Result<(), Failure> {
+    match command {
+        Command::Solve(runs) => solve(runs),
+        Command::Bench {
+            runs,
+            format,
+            out,
+            baseline,
+            threshold,
+        } => bench(runs, format, out, baseline, threshold),
+        Command::Verify { file, n } => {
+            let report = verify_file(&file, n)?;
+            println!(
+                "| 📂 {file} | 🇳 {:>4} | ⭕️ {:>10} | 📌 {:?} |",
+                report.order.try_n_from_order()?,
+                report.order,
+                report.id
+            );
+            Ok(())
+        }
+        Command::Export {
+            n,
+            format,
+            out,
+            merge,
+        } => {
+            export_tour(n, merge, format, &out)?;
+            println!("| 🇳 {n:>4} | ⭕️ {:>10} | 📂 {out} |", n.get_order_from_n());
+            Ok(())
+        }
+        Command::Encode { n, out } => {
+            let code = weave(n)?.encode();
+            match out {
+                Some(out) => std::fs::write(out, code)?,
+                None => println!("{code}"),
+            }
+            Ok(())
+        }
+        Command::Decode {
+            file,
+            n,
+            format,
+            out,
+        } => {
+            let tour = std::fs::read_to_string(&file)?
+                .trim()
+                .to_string()
+                .try_decode()?;
+            let n = match n {
+                Some(n) => n,
+                None => tour.len().try_n_from_order()?,
+            };
+            let solution = HamCycle::try_from(tour, n)?;
+            match out {
+                Some(out) => write_tour(&solution, format, &out)?,
+                None => println!(
+                    "| 📂 {file} | 🇳 {n:>4} | ⭕️ {:>10} | 📌 {:?} |",
+                    solution.order(),
+                    SequenceID::HamCycle
+                ),
+            }
+            Ok(())
+        }
+        Command::Grade { n } => {
+            let solution = weave(n)?;
+            let [x, y, z] = solution.axis_percent();
+            println!(
+                "| 🇳 {n:>4} | ⭕️ {:>10} | X {x:.2}% | Y {y:.2}% | Z {z:.2}% | NONTURNS {} |",
+                solution.order(),
+                solution.nonturns()
+            );
+            Ok(())
+        }
+        Command::Info { n } => {
+            let order = n.checked_order_from_n()?;
+            let codes = WvtHeader {
+                version: 0,
+                n: n as u64,
+                order: order as u64,
+                start: [0; 3],
+                checksum: 0,
+            }
+            .packed_len();
+            println!("n:               {n}");
+            println!("order:           {order}");
+            println!("max l1 norm:     {}", n.get_max_absumv());
+            println!("spool size:      {}", n.get_spool_size());
+            println!("loom size:       {}", n.loom_size());
+            println!("wvt bytes:       {}", WVT_HEADER_LEN as u64 + codes);
+            println!("ranks fit u32:   {}", u32::try_from(order - 1).is_ok());
+            Ok(())
+        }
+        Command::Profile {
+            n,
+            merge,
+            format,
+            out,
+        } => {
+            let (_, profile) = weave_profiled(n, merge)?;
+            let profile = match format {
+                ProfileFormat::Table => profile.to_string(),
+                ProfileFormat::Json => profile.to_json()? + "\n",
+            };
+            match out {
+                Some(out) => std::fs::write(out, profile)?,
+                None => print!("{profile}"),
+            }
+            Ok(())
+        }
+        Command::Help => {
+            println!("{USAGE}");
+            Ok(())
+        }
+    }
+}
+
+/// Weave, time and certify each n with `bench_n`, reporting a failing instance and continuing with the rest of the batch.
+/// Fails at the end if any n failed.
+fn solve(Runs { ns, repeats, merge }: Runs) -> Result<(), Failure> {
     println!("{}", get_current_date_time());
-    let args: Vec<String> = env::args().collect();
-    let n_start = args
-        .get(1)
-        .and_then(|arg| arg.parse().ok())
-        .filter(|&parsed| parsed > 0)
-        .unwrap_or(100);
-    let n_end = args
-        .get(2)
-        .and_then(|arg| arg.parse().ok())
-        .filter(|&parsed| parsed >= n_start)
-        .unwrap_or(n_start);
-    let steps = args.get(3).and_then(|arg| arg.parse().ok()).unwrap_or(1);
-    let repeats = args.get(4).and_then(|arg| arg.parse().ok()).unwrap_or(1);
-    for level in (n_start..=n_end).step_by(steps) {
-        find_solutions(level, repeats)?;
+    let failed = ns
+        .into_iter()
+        .filter(|&n| {
+            if n.checked_order_from_n()
+                .is_ok_and(|order| order > 1_000_000_000)
+            {
+                println!(
+                    "{} | SOLVING ORDER ⭕️ {}",
+                    get_current_date_time(),
+                    n.get_order_from_n()
+                );
+            }
+            match bench_n(n, repeats, merge) {
+                Ok(record) => {
+                    println!(
+                        "| 🇳 {n:>4} | ⭕️ {:>10} | 🕗 SOLVE: {:.10} | 📌 {:?} | 🕗 CERTIFY: {:.10}",
+                        record.order,
+                        record.min,
+                        SequenceID::HamCycle,
+                        record.certify
+                    );
+                    false
+                }
+                Err(error) => {
+                    println!("| 🇳 {n:>4} | ❌ {error} |");
+                    true
+                }
+            }
+        })
+        .count();
+    match failed {
+        0 => Ok(()),
+        failed => Err(Failure::Batch { failed, slower: 0 }),
     }
-    Ok(())
 }
 
-// Solve on one or many by step or by steps. Time it and certify.
-pub fn find_solutions(n: usize, repeats: usize) -> Result<Solution, &'static str> {
-    let order = n.get_order_from_n();
-    let mut solution = Solution::new();
-    let mut _start: Instant = Instant::now();
-    if order > 1000000000 {
-        println!("{} | SOLVING ORDER ⭕️ {order}", get_current_date_time());
+/// Bench each n, then write the records and compare them with the baseline.
+/// Rows are printed as each n finishes, to stderr if the records themselves go to stdout.
+/// Fails if any n failed or is slower than its baseline by more than `threshold` percent.
+fn bench(
+    Runs { ns, repeats, merge }: Runs,
+    format: BenchFormat,
+    out: Option<String>,
+    baseline: Option<String>,
+    threshold: f64,
+) -> Result<(), Failure> {
+    let baseline = baseline
+        .map(|file_path| read_baseline(&file_path))
+        .transpose()?;
+    let rows_to_stdout = format == BenchFormat::Table || out.is_some();
+    let mut failed = 0;
+    let records = ns
+        .into_iter()
+        .filter_map(|n| match bench_n(n, repeats, merge) {
+            Ok(record) => {
+                let row = bench_row(&record);
+                match rows_to_stdout {
+                    true => println!("{row}"),
+                    false => eprintln!("{row}"),
+                }
+                Some(record)
+            }
+            Err(error) => {
+                eprintln!("| 🇳 {n:>4} | ❌ {error} |");
+                failed += 1;
+                None
+            }
+        })
+        .collect::<Vec<_>>();
+    match (out, format) {
+        (Some(out), _) => write_records(&records, format, &out)?,
+        (None, BenchFormat::Table) => {}
+        (None, _) => print!("{}", render(&records, format)?),
+    }
+    let slower = baseline
+        .map(|baseline| regressions(&records, &baseline, threshold))
+        .unwrap_or_default();
+    slower.iter().for_each(|regression| {
+        eprintln!(
+            "| 🇳 {:>4} | 🐢 MEDIAN {:.10} | BASELINE {:.10} | +{:.1}% > {threshold}% |",
+            regression.n, regression.median, regression.baseline, regression.slower
+        )
+    });
+    match (failed, slower.len()) {
+        (0, 0) => Ok(()),
+        (failed, slower) => Err(Failure::Batch { failed, slower }),
     }
-    let mut min_dur = Duration::new(1000000, 0);
-    for _ in 0..repeats {
-        let start = Instant::now();
-        solution = weave::weave(n);
-        let dur_solve = Instant::now() - start;
-        if dur_solve < min_dur {
-            min_dur = dur_solve;
+}
+
+/// Every statistic of the runs of one n, in the style of the rows of `solve`.
+fn bench_row(record: &BenchRecord) -> String {
+    let rss = match record.peak_rss_kb {
+        Some(kb) => format!("{:.1} MB", kb as f64 / 1024.0),
+        None => "-".to_string(),
+    };
+    format!(
+        "| 🇳 {:>4} | ⭕️ {:>10} | 🕗 MIN {:.10} | MEDIAN {:.10} | MEAN {:.10} | STDDEV {:.10} | P95 {:.10} | 🕗 CERTIFY: {:.10} | 💾 {rss} |",
+        record.n, record.order, record.min, record.median, record.mean, record.stddev, record.p95, record.certify
+    )
+}
+
+/// Weave n into the file at `file_path` in the format given.
+/// Joined one by one the warps are streamed into the sink by `weave_into()`, so the tour is never held whole: only a tree merge or json, serialized at once, weave it first.
+fn export_tour(
+    n: usize,
+    merge: MergeStrategy,
+    format: Format,
+    file_path: &str,
+) -> Result<(), WeaveError> {
+    match (merge, format) {
+        (MergeStrategy::Sequential, Format::Csv) => weave_into(n, &mut CsvSink::create(file_path)?),
+        (MergeStrategy::Sequential, Format::Wvt) => {
+            weave_into(n, &mut WvtSink::create(file_path, n)?)
         }
+        (MergeStrategy::Sequential, Format::Bin) => weave_into(
+            n,
+            &mut BinarySink::new(BufWriter::new(File::create(file_path)?)),
+        ),
+        _ => write_tour(&weave_with(n, merge)?, format, file_path),
     }
-    // After a billion the amount of data required to certify sequence gets so large... printing to csv and validating is better.
-    if order > 100000000 {
-        println!(
-            "| 🇳 {n:>4} | ⭕️ {order:>10} | 🕗 {:.10} |",
-            min_dur.as_secs_f32(),
-        );
-    } else {
-        _start = Instant::now();
-        let seq_id = solution.certify(order, n.get_max_absumv());
-        let _dur_certify = Instant::now() - _start;
-        println!(
-            "| 🇳 {n:>4} | ⭕️ {order:>10} | 🕗 SOLVE: {:.10} | 📌 {seq_id:?} | 🕗 CERTIFY: {:.10}",
-            min_dur.as_secs_f32(),
-            _dur_certify.as_secs_f32()
-        );
-        assert_eq!(seq_id, SequenceID::HamCycle);
+}
+
+/// Write the tour to the file at `file_path` in the format given.
+fn write_tour(solution: &HamCycle, format: Format, file_path: &str) -> Result<(), WeaveError> {
+    let put_all = |sink: &mut dyn TourSink| -> Result<(), WeaveError> {
+        solution.iter().try_for_each(|&vert| sink.put(vert))?;
+        sink.finish()
+    };
+    match format {
+        Format::Csv => put_all(&mut CsvSink::create(file_path)?),
+        Format::Wvt => put_all(&mut WvtSink::create(file_path, solution.n())?),
+        Format::Bin => put_all(&mut BinarySink::new(BufWriter::new(File::create(
+            file_path,
+        )?))),
+        Format::Json => {
+            serde_json::to_writer(BufWriter::new(File::create(file_path)?), &**solution)?;
+            Ok(())
+        }
     }
-    Ok(solution)
 }